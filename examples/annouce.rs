@@ -1,4 +1,5 @@
 extern crate multicast_dns;
+use multicast_dns::discovery::TxtRecord;
 use multicast_dns::host::*;
 
 fn main() {
@@ -7,7 +8,19 @@ fn main() {
 
     let host_manager = HostManager::new();
 
-    host_manager.announce_service("my local service", service_type, port).unwrap();
+    let mut txt = TxtRecord::new();
+    txt.insert("version", "1.0").unwrap();
+    txt.insert("path", "/api").unwrap();
+
+    let registration = ServiceRegistration::new("my local service", service_type, port)
+        .subtype("_example")
+        .txt(txt);
+
+    // Service stays announced for as long as we keep the handle.
+    let service = host_manager.register_service(registration).unwrap();
+
+    // Name can differ from the requested one if there was a name collision.
+    println!("Service is announced as: {:?}", service.name().unwrap());
 
     println!("Press enter to exit example");
     std::io::stdin().read_line(&mut String::new()).unwrap();
@@ -1,33 +1,52 @@
 extern crate multicast_dns;
 use multicast_dns::discovery::*;
+use multicast_dns::errors::Error;
+use std::time::Duration;
 
 fn main() {
     let service_type = format!("_device-info._tcp");
 
     let discovery_manager = DiscoveryManager::new();
 
-    let on_service_resolved = |service: ServiceInfo| {
+    let on_service_resolved = |service: ResolvedService| {
         println!("Service resolved: {:?}", service);
     };
 
-    let on_service_discovered = |service: ServiceInfo| {
-        println!("Service discovered: {:?}", service);
-
-        let resolve_listeners = ResolveListeners {
-            on_service_resolved: Some(&on_service_resolved),
-        };
-
-        discovery_manager.resolve_service(service, resolve_listeners);
-    };
-
-    let on_all_discovered = || {
-        println!("All services has been discovered");
-    };
-
-    let discovery_listeners = DiscoveryListeners {
-        on_service_discovered: Some(&on_service_discovered),
-        on_all_discovered: Some(&on_all_discovered),
+    let on_resolve_failed = |error: Error| {
+        println!("Service resolution failed: {}", error);
     };
 
-    discovery_manager.discover_services(&service_type, discovery_listeners).unwrap();
-}
\ No newline at end of file
+    // Discovery runs in the background until the session is stopped or dropped.
+    let session = discovery_manager.start_discovery(&service_type).unwrap();
+
+    for event in session.iter() {
+        match event {
+            DiscoveryEvent::ServiceDiscovered(service) => {
+                println!("Service discovered: {:?}", service);
+
+                let resolve_listeners = ResolveListeners {
+                    on_service_resolved: Some(&on_service_resolved),
+                    on_resolve_failed: Some(&on_resolve_failed),
+                };
+
+                // Resolution errors are reported to `on_resolve_failed` listener.
+                let timeout = Some(Duration::from_secs(5));
+                let _ = discovery_manager.resolve_service(&service, resolve_listeners, timeout);
+            }
+            DiscoveryEvent::ServiceRemoved(service) => {
+                println!("Service removed: {:?}", service);
+            }
+            DiscoveryEvent::AllDiscovered => {
+                println!("All services has been discovered");
+                break;
+            }
+            DiscoveryEvent::Failed(error) => {
+                println!("Discovery failed: {}", error);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    session.stop();
+}
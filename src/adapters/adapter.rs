@@ -1,24 +1,157 @@
+//! Interface every mDNS adapter implements. Adapters report results through handlers
+//! that may be called from the adapter's own thread rather than the calling one, so
+//! handlers have to be `Send`.
+
+use std::net::IpAddr;
+
 use adapters::errors::Error;
 use discovery::discovery_manager::*;
+use discovery::discovery_options::DiscoveryOptions;
+use discovery::dns_record::RecordType;
+use discovery::lookup_flags::LookupFlags;
+use discovery::txt_record::TxtRecord;
+use host::address_registration::AddressRegistration;
+use host::host_manager::{PublishEvent, ServiceEvent};
+use host::name_alias::{AliasState, NameAlias};
+use host::record_registration::RecordRegistration;
+use host::service_registration::ServiceRegistration;
+
+/// Handler that receives discovery events.
+pub type DiscoveryEventHandler = Box<dyn Fn(DiscoveryEvent) + Send>;
+
+/// Handler that receives service type discovery events.
+pub type ServiceTypeEventHandler = Box<dyn Fn(ServiceTypeEvent) + Send>;
+
+/// Handler that receives domain discovery events.
+pub type DomainEventHandler = Box<dyn Fn(DomainEvent) + Send>;
+
+/// Handler that receives DNS record events.
+pub type RecordEventHandler = Box<dyn Fn(RecordEvent) + Send>;
+
+/// Handler that receives resolved service or resolution error.
+pub type ResolveEventHandler = Box<dyn Fn(Result<ServiceInfo, Error>) + Send>;
+
+/// Handler that receives resolved host or resolution error.
+pub type HostResolveEventHandler = Box<dyn Fn(Result<HostInfo, Error>) + Send>;
+
+/// Handler that receives announced service events.
+pub type ServiceEventHandler = Box<dyn Fn(ServiceEvent) + Send>;
+
+/// Handler that receives published records events.
+pub type PublishEventHandler = Box<dyn Fn(PublishEvent) + Send>;
 
-pub trait DiscoveryAdapter {
+pub trait DiscoveryAdapter: Send + Sync {
+    /// Starts discovery of services of `service_type` on the interface, over the
+    /// protocol and in the domain specified in `options`.
     fn start_discovery(
         &self,
         service_type: &str,
-        listeners: DiscoveryListeners,
-    ) -> Result<(), Error>;
-    fn resolve(&self, service: ServiceInfo, listeners: ResolveListeners);
-    fn stop_discovery(&self);
+        options: &DiscoveryOptions,
+        handler: DiscoveryEventHandler,
+    ) -> Result<usize, Error>;
+    /// Starts resolution of the service, `address_protocol` specifies family of the
+    /// address to look for.
+    fn start_resolve(
+        &self,
+        service: ServiceInfo,
+        address_protocol: ServiceProtocol,
+        flags: LookupFlags,
+        handler: ResolveEventHandler,
+    ) -> Result<usize, Error>;
+    fn stop_resolve(&self, resolver_id: usize);
+    /// Starts resolution of the host name to the address of `address_protocol` family
+    /// on the interface and over the protocol specified in `options`.
+    fn start_host_name_resolve(
+        &self,
+        host_name: &str,
+        address_protocol: ServiceProtocol,
+        options: &DiscoveryOptions,
+        handler: HostResolveEventHandler,
+    ) -> Result<usize, Error>;
+    fn stop_host_name_resolve(&self, resolver_id: usize);
+    /// Starts resolution of the address to the host name on the interface and over the
+    /// protocol specified in `options`.
+    fn start_address_resolve(
+        &self,
+        address: IpAddr,
+        options: &DiscoveryOptions,
+        handler: HostResolveEventHandler,
+    ) -> Result<usize, Error>;
+    fn stop_address_resolve(&self, resolver_id: usize);
+    /// Starts discovery of service types advertised on the interface, over the protocol
+    /// and in the domain specified in `options`. Discovery is stopped with
+    /// `stop_discovery`.
+    fn start_service_type_discovery(
+        &self,
+        options: &DiscoveryOptions,
+        handler: ServiceTypeEventHandler,
+    ) -> Result<usize, Error>;
+    /// Starts discovery of domains of `domain_type` on the interface, over the protocol
+    /// and in the domain specified in `options`. Discovery is stopped with
+    /// `stop_discovery`.
+    fn start_domain_discovery(
+        &self,
+        domain_type: DomainType,
+        options: &DiscoveryOptions,
+        handler: DomainEventHandler,
+    ) -> Result<usize, Error>;
+    /// Starts browsing for DNS records with the specified name, class and type on the
+    /// interface and over the protocol specified in `options`. Discovery is stopped
+    /// with `stop_discovery`.
+    fn start_record_discovery(
+        &self,
+        name: &str,
+        record_class: u16,
+        record_type: RecordType,
+        options: &DiscoveryOptions,
+        handler: RecordEventHandler,
+    ) -> Result<usize, Error>;
+    /// Stops discovery started with any of `start_*discovery` methods.
+    fn stop_discovery(&self, browser_id: usize);
+    fn stop_all_discovery(&self);
 }
 
-pub trait HostAdapter {
+pub trait HostAdapter: Send + Sync {
     fn get_name(&self) -> Result<String, Error>;
     fn get_name_fqdn(&self) -> Result<String, Error>;
     fn set_name(&self, host_name: &str) -> Result<String, Error>;
     fn is_valid_name(&self, host_name: &str) -> Result<bool, Error>;
     fn get_alternative_name(&self, host_name: &str) -> Result<String, Error>;
-    fn add_name_alias(&self, host_name: &str) -> Result<(), Error>;
-    fn announce_service(&self, service_name: &str, service_type: &str, port: u16) -> Result<(), Error>;
+    /// Publishes host name alias, alias with the same name is replaced. Aliases are
+    /// never renamed, collision is reported as failure.
+    fn add_name_alias(&self, alias: &NameAlias, handler: PublishEventHandler) -> Result<(), Error>;
+    fn remove_name_alias(&self, name: &str) -> Result<(), Error>;
+    fn get_name_aliases(&self) -> Vec<(NameAlias, AliasState)>;
+    fn get_alternative_service_name(&self, service_name: &str) -> Result<String, Error>;
+    fn register_service(
+        &self,
+        registration: &ServiceRegistration,
+        handler: ServiceEventHandler,
+    ) -> Result<usize, Error>;
+    /// Replaces TXT record of the service on every interface it's published on. Update
+    /// isn't atomic: if it fails on one of the interfaces, the interfaces updated
+    /// before it keep the new record and error lists them.
+    fn update_service_txt(&self, service_id: usize, txt: &TxtRecord) -> Result<(), Error>;
+    fn reset_service(&self, service_id: usize) -> Result<(), Error>;
+    fn republish_service(&self, service_id: usize) -> Result<(), Error>;
+    fn unregister_service(&self, service_id: usize);
+    fn get_service_name(&self, service_id: usize) -> Result<String, Error>;
+    /// Publishes DNS record, record data is encoded before anything is published.
+    fn publish_record(
+        &self,
+        registration: &RecordRegistration,
+        handler: PublishEventHandler,
+    ) -> Result<usize, Error>;
+    /// Publishes address record for the host `name` along with the reverse PTR record,
+    /// unless `flags` contain `NO_REVERSE`. Host name is changed on collisions.
+    fn publish_address(
+        &self,
+        registration: &AddressRegistration,
+        handler: PublishEventHandler,
+    ) -> Result<usize, Error>;
+    /// Withdraws records published with any of `publish_*` methods.
+    fn unpublish_record(&self, record_id: usize);
+    fn get_record_name(&self, record_id: usize) -> Result<String, Error>;
 }
 
 pub trait Adapter: DiscoveryAdapter + HostAdapter + Drop {
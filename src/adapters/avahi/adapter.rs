@@ -1,12 +1,26 @@
-use std::cell::Cell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::ffi::CString;
+use std::net::IpAddr;
 use std::ptr;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 
-use libc::c_void;
+use libc::{c_int, c_void};
 
 use bindings::avahi::*;
 use discovery::discovery_manager::*;
+use discovery::discovery_options::DiscoveryOptions;
+use discovery::dns_record::{RecordData, RecordType};
+use discovery::lookup_flags::{LookupFlags, LookupResultFlags};
+use discovery::service::DiscoveredService;
+use discovery::txt_record::TxtRecord;
+use host::address_registration::AddressRegistration;
+use host::host_manager::{PublishEvent, ServiceEvent};
+use host::name_alias::{AliasKind, AliasState, NameAlias};
+use host::publish_flags::PublishFlags;
+use host::record_registration::RecordRegistration;
+use host::service_registration::ServiceRegistration;
 
 use adapters::adapter::*;
 use adapters::avahi::callbacks::*;
@@ -15,18 +29,105 @@ use adapters::avahi::utils::*;
 use adapters::errors::Error as AdapterError;
 
 pub struct Channel<T> {
-    pub receiver: mpsc::Receiver<T>,
+    pub receiver: Mutex<mpsc::Receiver<T>>,
     pub sender: mpsc::Sender<T>,
 }
 
 pub struct AvahiAdapter {
-    poll: Cell<Option<*mut AvahiThreadedPoll>>,
+    poll: Mutex<Option<*mut AvahiThreadedPoll>>,
 
-    client: Cell<Option<*mut AvahiClient>>,
+    /// Client is created on the first use, the lock is held while it's being created.
+    client: Mutex<Option<*mut AvahiClient>>,
     client_channel: Channel<ClientCallbackParameters>,
 
-    service_browser: Cell<Option<*mut AvahiServiceBrowser>>,
-    service_browser_channel: Channel<Option<BrowseCallbackParameters>>,
+    service_browsers: Mutex<HashMap<usize, ServiceBrowserEntry>>,
+    service_type_browsers: Mutex<HashMap<usize, ServiceTypeBrowserEntry>>,
+    domain_browsers: Mutex<HashMap<usize, DomainBrowserEntry>>,
+    record_browsers: Mutex<HashMap<usize, RecordBrowserEntry>>,
+
+    service_resolvers: Mutex<HashMap<usize, ServiceResolverEntry>>,
+    host_name_resolvers: Mutex<HashMap<usize, HostNameResolverEntry>>,
+    address_resolvers: Mutex<HashMap<usize, AddressResolverEntry>>,
+
+    services: Mutex<HashMap<usize, ServiceEntry>>,
+    records: Mutex<HashMap<usize, RecordEntry>>,
+    aliases: Mutex<HashMap<String, AliasEntry>>,
+
+    next_id: AtomicUsize,
+}
+
+// Avahi objects are only touched with the threaded poll locked (see `with_poll_lock`),
+// while the rest of the state is guarded by mutexes, so adapter can be shared between
+// threads.
+unsafe impl Send for AvahiAdapter {}
+unsafe impl Sync for AvahiAdapter {}
+
+/// Running Avahi browser or resolver along with the handler it reports to.
+struct LookupEntry<T, H> {
+    object: *mut T,
+    handler: *mut H,
+}
+
+impl<T, H> Drop for LookupEntry<T, H> {
+    /// Avahi object must be freed (or not used anymore) by the time entry is dropped.
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.handler)) };
+    }
+}
+
+type ServiceBrowserEntry = LookupEntry<AvahiServiceBrowser, BrowseCallbackHandler>;
+type ServiceTypeBrowserEntry =
+    LookupEntry<AvahiServiceTypeBrowser, ServiceTypeBrowseCallbackHandler>;
+type DomainBrowserEntry = LookupEntry<AvahiDomainBrowser, DomainBrowseCallbackHandler>;
+type RecordBrowserEntry = LookupEntry<AvahiRecordBrowser, RecordBrowseCallbackHandler>;
+type ServiceResolverEntry = LookupEntry<AvahiServiceResolver, ResolveCallbackHandler>;
+type HostNameResolverEntry = LookupEntry<AvahiHostNameResolver, HostNameResolveCallbackHandler>;
+type AddressResolverEntry = LookupEntry<AvahiAddressResolver, AddressResolveCallbackHandler>;
+
+/// Announced service along with the entry group it's published with. Registration is
+/// shared with the entry group handler that renames service on name collisions.
+struct ServiceEntry {
+    entry_group: *mut AvahiEntryGroup,
+    handler: *mut EntryGroupCallbackHandler,
+    registration: Arc<Mutex<ServiceRegistration>>,
+}
+
+impl Drop for ServiceEntry {
+    /// Entry group must be freed (or not used anymore) by the time entry is dropped.
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.handler)) };
+    }
+}
+
+/// Published records along with the entry group they're published with. Name is shared
+/// with the entry group handler that renames address records on host name collisions.
+struct RecordEntry {
+    entry_group: *mut AvahiEntryGroup,
+    handler: *mut EntryGroupCallbackHandler,
+    name: Arc<Mutex<String>>,
+}
+
+impl Drop for RecordEntry {
+    /// Entry group must be freed (or not used anymore) by the time entry is dropped.
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.handler)) };
+    }
+}
+
+/// Host name alias along with the entry group it's published with. State is updated by
+/// the entry group handler.
+struct AliasEntry {
+    entry_group: *mut AvahiEntryGroup,
+    handler: *mut EntryGroupCallbackHandler,
+    alias: NameAlias,
+    state: Arc<Mutex<AliasState>>,
+}
+
+impl Drop for AliasEntry {
+    /// Entry group must be freed (or not used anymore) by the time entry is dropped.
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.handler)) };
+    }
 }
 
 fn avahi_protocol_to_service_protocol(protocol: AvahiProtocol) -> ServiceProtocol {
@@ -37,6 +138,288 @@ fn avahi_protocol_to_service_protocol(protocol: AvahiProtocol) -> ServiceProtoco
     }
 }
 
+fn lookup_flags_to_avahi_flags(flags: LookupFlags) -> c_int {
+    let flag_pairs = [
+        (
+            LookupFlags::USE_WIDE_AREA,
+            AvahiLookupFlags::AVAHI_LOOKUP_USE_WIDE_AREA as c_int,
+        ),
+        (
+            LookupFlags::USE_MULTICAST,
+            AvahiLookupFlags::AVAHI_LOOKUP_USE_MULTICAST as c_int,
+        ),
+        (
+            LookupFlags::NO_TXT,
+            AvahiLookupFlags::AVAHI_LOOKUP_NO_TXT as c_int,
+        ),
+        (
+            LookupFlags::NO_ADDRESS,
+            AvahiLookupFlags::AVAHI_LOOKUP_NO_ADDRESS as c_int,
+        ),
+    ];
+
+    flag_pairs
+        .iter()
+        .filter(|&&(flag, _)| flags.contains(flag))
+        .fold(0, |avahi_flags, &(_, avahi_flag)| avahi_flags | avahi_flag)
+}
+
+fn avahi_flags_to_lookup_result_flags(avahi_flags: c_int) -> LookupResultFlags {
+    let flag_pairs = [
+        (
+            LookupResultFlags::CACHED,
+            AvahiLookupResultFlags::AVAHI_LOOKUP_RESULT_CACHED as c_int,
+        ),
+        (
+            LookupResultFlags::WIDE_AREA,
+            AvahiLookupResultFlags::AVAHI_LOOKUP_RESULT_WIDE_AREA as c_int,
+        ),
+        (
+            LookupResultFlags::MULTICAST,
+            AvahiLookupResultFlags::AVAHI_LOOKUP_RESULT_MULTICAST as c_int,
+        ),
+        (
+            LookupResultFlags::LOCAL,
+            AvahiLookupResultFlags::AVAHI_LOOKUP_RESULT_LOCAL as c_int,
+        ),
+        (
+            LookupResultFlags::OUR_OWN,
+            AvahiLookupResultFlags::AVAHI_LOOKUP_RESULT_OUR_OWN as c_int,
+        ),
+        (
+            LookupResultFlags::STATIC,
+            AvahiLookupResultFlags::AVAHI_LOOKUP_RESULT_STATIC as c_int,
+        ),
+    ];
+
+    flag_pairs
+        .iter()
+        .filter(|&&(_, avahi_flag)| avahi_flags & avahi_flag != 0)
+        .fold(LookupResultFlags::empty(), |flags, &(flag, _)| flags | flag)
+}
+
+fn browse_parameters_to_event(
+    service_browser: *mut AvahiServiceBrowser,
+    parameters: BrowseCallbackParameters,
+) -> DiscoveryEvent {
+    let service = ServiceInfo {
+        address: None,
+        addresses: Vec::new(),
+        domain: parameters.domain,
+        host_name: None,
+        interface: parameters.interface,
+        name: parameters.name,
+        port: 0,
+        protocol: avahi_protocol_to_service_protocol(parameters.protocol),
+        txt: None,
+        type_name: parameters.service_type,
+        flags: avahi_flags_to_lookup_result_flags(parameters.flags),
+    };
+
+    match parameters.event {
+        AvahiBrowserEvent::AVAHI_BROWSER_NEW => match DiscoveredService::try_from(service) {
+            Ok(service) => DiscoveryEvent::ServiceDiscovered(service),
+            Err(error) => DiscoveryEvent::Failed(error),
+        },
+        AvahiBrowserEvent::AVAHI_BROWSER_REMOVE => match DiscoveredService::try_from(service) {
+            Ok(service) => DiscoveryEvent::ServiceRemoved(service),
+            Err(error) => DiscoveryEvent::Failed(error),
+        },
+        AvahiBrowserEvent::AVAHI_BROWSER_CACHE_EXHAUSTED => DiscoveryEvent::CacheExhausted,
+        AvahiBrowserEvent::AVAHI_BROWSER_ALL_FOR_NOW => DiscoveryEvent::AllDiscovered,
+        AvahiBrowserEvent::AVAHI_BROWSER_FAILURE => {
+            let error_code =
+                unsafe { avahi_client_errno(avahi_service_browser_get_client(service_browser)) };
+            let error = AvahiError::from_error_code(error_code);
+            error!("Service browser failed: {}", error);
+
+            DiscoveryEvent::Failed(From::from(error))
+        }
+    }
+}
+
+fn service_type_browse_parameters_to_event(
+    service_type_browser: *mut AvahiServiceTypeBrowser,
+    parameters: ServiceTypeBrowseCallbackParameters,
+) -> ServiceTypeEvent {
+    let service_type = ServiceTypeInfo {
+        service_type: parameters.service_type.unwrap_or_default(),
+        domain: parameters.domain.unwrap_or_default(),
+        interface: parameters.interface,
+        protocol: avahi_protocol_to_service_protocol(parameters.protocol),
+    };
+
+    match parameters.event {
+        AvahiBrowserEvent::AVAHI_BROWSER_NEW => {
+            ServiceTypeEvent::ServiceTypeDiscovered(service_type)
+        }
+        AvahiBrowserEvent::AVAHI_BROWSER_REMOVE => {
+            ServiceTypeEvent::ServiceTypeRemoved(service_type)
+        }
+        AvahiBrowserEvent::AVAHI_BROWSER_CACHE_EXHAUSTED => ServiceTypeEvent::CacheExhausted,
+        AvahiBrowserEvent::AVAHI_BROWSER_ALL_FOR_NOW => ServiceTypeEvent::AllDiscovered,
+        AvahiBrowserEvent::AVAHI_BROWSER_FAILURE => {
+            let error_code = unsafe {
+                avahi_client_errno(avahi_service_type_browser_get_client(service_type_browser))
+            };
+            let error = AvahiError::from_error_code(error_code);
+            error!("Service type browser failed: {}", error);
+
+            ServiceTypeEvent::Failed(From::from(error))
+        }
+    }
+}
+
+fn domain_type_to_avahi_domain_browser_type(domain_type: DomainType) -> AvahiDomainBrowserType {
+    match domain_type {
+        DomainType::Browse => AvahiDomainBrowserType::AVAHI_DOMAIN_BROWSER_BROWSE,
+        DomainType::BrowseDefault => AvahiDomainBrowserType::AVAHI_DOMAIN_BROWSER_BROWSE_DEFAULT,
+        DomainType::Register => AvahiDomainBrowserType::AVAHI_DOMAIN_BROWSER_REGISTER,
+        DomainType::RegisterDefault => {
+            AvahiDomainBrowserType::AVAHI_DOMAIN_BROWSER_REGISTER_DEFAULT
+        }
+        DomainType::BrowseLegacy => AvahiDomainBrowserType::AVAHI_DOMAIN_BROWSER_BROWSE_LEGACY,
+    }
+}
+
+fn domain_browse_parameters_to_event(
+    domain_browser: *mut AvahiDomainBrowser,
+    parameters: DomainBrowseCallbackParameters,
+) -> DomainEvent {
+    let domain = DomainInfo {
+        domain: parameters.domain.unwrap_or_default(),
+        interface: parameters.interface,
+        protocol: avahi_protocol_to_service_protocol(parameters.protocol),
+    };
+
+    match parameters.event {
+        AvahiBrowserEvent::AVAHI_BROWSER_NEW => DomainEvent::DomainDiscovered(domain),
+        AvahiBrowserEvent::AVAHI_BROWSER_REMOVE => DomainEvent::DomainRemoved(domain),
+        AvahiBrowserEvent::AVAHI_BROWSER_CACHE_EXHAUSTED => DomainEvent::CacheExhausted,
+        AvahiBrowserEvent::AVAHI_BROWSER_ALL_FOR_NOW => DomainEvent::AllDiscovered,
+        AvahiBrowserEvent::AVAHI_BROWSER_FAILURE => {
+            let error_code =
+                unsafe { avahi_client_errno(avahi_domain_browser_get_client(domain_browser)) };
+            let error = AvahiError::from_error_code(error_code);
+            error!("Domain browser failed: {}", error);
+
+            DomainEvent::Failed(From::from(error))
+        }
+    }
+}
+
+fn record_browse_parameters_to_event(
+    record_browser: *mut AvahiRecordBrowser,
+    parameters: RecordBrowseCallbackParameters,
+) -> RecordEvent {
+    let record_type = RecordType::from_u16(parameters.record_type);
+    let record = RecordInfo {
+        name: parameters.name.unwrap_or_default(),
+        record_class: parameters.record_class,
+        record_type,
+        data: RecordData::decode(record_type, &parameters.data),
+        interface: parameters.interface,
+        protocol: avahi_protocol_to_service_protocol(parameters.protocol),
+    };
+
+    match parameters.event {
+        AvahiBrowserEvent::AVAHI_BROWSER_NEW => RecordEvent::RecordDiscovered(record),
+        AvahiBrowserEvent::AVAHI_BROWSER_REMOVE => RecordEvent::RecordRemoved(record),
+        AvahiBrowserEvent::AVAHI_BROWSER_CACHE_EXHAUSTED => RecordEvent::CacheExhausted,
+        AvahiBrowserEvent::AVAHI_BROWSER_ALL_FOR_NOW => RecordEvent::AllDiscovered,
+        AvahiBrowserEvent::AVAHI_BROWSER_FAILURE => {
+            let error_code =
+                unsafe { avahi_client_errno(avahi_record_browser_get_client(record_browser)) };
+            let error = AvahiError::from_error_code(error_code);
+            error!("Record browser failed: {}", error);
+
+            RecordEvent::Failed(From::from(error))
+        }
+    }
+}
+
+fn resolve_parameters_to_result(
+    service_resolver: *mut AvahiServiceResolver,
+    parameters: ResolveCallbackParameters,
+) -> Result<ServiceInfo, AdapterError> {
+    if let AvahiResolverEvent::AVAHI_RESOLVER_FAILURE = parameters.event {
+        let error_code =
+            unsafe { avahi_client_errno(avahi_service_resolver_get_client(service_resolver)) };
+        let error = AvahiError::from_error_code(error_code);
+        error!("Service resolver failed: {}", error);
+
+        return Err(From::from(error));
+    }
+
+    let mut service = ServiceInfo {
+        address: parameters.address,
+        addresses: Vec::new(),
+        domain: parameters.domain,
+        host_name: parameters.host_name,
+        interface: parameters.interface,
+        name: parameters.name,
+        port: parameters.port,
+        protocol: avahi_protocol_to_service_protocol(parameters.protocol),
+        txt: parameters.txt,
+        type_name: parameters.service_type,
+        flags: avahi_flags_to_lookup_result_flags(parameters.flags),
+    };
+    service.addresses.extend(service.socket_addr());
+
+    Ok(service)
+}
+
+/// Converts host name or address resolution result into `HostInfo`, `client` is the
+/// parent client of the resolver that is used to get failure reason.
+fn host_name_resolve_parameters_to_result(
+    client: *mut AvahiClient,
+    parameters: HostNameResolveCallbackParameters,
+) -> Result<HostInfo, AdapterError> {
+    if let AvahiResolverEvent::AVAHI_RESOLVER_FAILURE = parameters.event {
+        let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+        error!("Host name or address resolver failed: {}", error);
+
+        return Err(From::from(error));
+    }
+
+    match (parameters.host_name, parameters.address) {
+        (Some(host_name), Some(address)) => Ok(HostInfo {
+            host_name,
+            address,
+            interface: parameters.interface,
+            protocol: avahi_protocol_to_service_protocol(parameters.protocol),
+        }),
+        _ => Err(AdapterError::Internal(
+            "Resolved host name or address is not available".to_owned(),
+        )),
+    }
+}
+
+/// Converts optional domain into C string, `None` stands for the default domain.
+fn domain_to_c_string(domain: Option<&str>) -> Result<Option<CString>, AdapterError> {
+    match domain {
+        Some(domain) => CString::new(domain)
+            .map(Some)
+            .map_err(|_| AdapterError::Internal("Domain contains NUL byte".to_owned())),
+        None => Ok(None),
+    }
+}
+
+/// Converts DNS record or host name into C string.
+fn name_to_c_string(name: &str) -> Result<CString, AdapterError> {
+    CString::new(name)
+        .map_err(|_| AdapterError::Internal(format!("Name {:?} contains NUL byte", name)))
+}
+
+/// Converts mandatory service field into C string, `field` is used in error message only.
+fn service_field_to_c_string(value: Option<String>, field: &str) -> Result<CString, AdapterError> {
+    let value = value
+        .ok_or_else(|| AdapterError::Internal(format!("Service {} is not specified", field)))?;
+
+    CString::new(value)
+        .map_err(|_| AdapterError::Internal(format!("Service {} contains NUL byte", field)))
+}
+
 fn service_protocol_to_avahi_protocol(protocol: ServiceProtocol) -> AvahiProtocol {
     match protocol {
         ServiceProtocol::IPv4 => AvahiProtocol::AVAHI_PROTO_INET,
@@ -45,18 +428,380 @@ fn service_protocol_to_avahi_protocol(protocol: ServiceProtocol) -> AvahiProtoco
     }
 }
 
-fn name_fqdn_to_cname_rdata(name_fqdn: &str) -> Vec<u8> {
-    let mut rdata: Vec<u8> = Vec::new();
+fn publish_flags_to_avahi_flags(flags: PublishFlags) -> c_int {
+    let flag_pairs = [
+        (PublishFlags::UNIQUE, AvahiPublishFlags::AVAHI_PUBLISH_UNIQUE as c_int),
+        (PublishFlags::NO_PROBE, AvahiPublishFlags::AVAHI_PUBLISH_NO_PROBE as c_int),
+        (PublishFlags::NO_ANNOUNCE, AvahiPublishFlags::AVAHI_PUBLISH_NO_ANNOUNCE as c_int),
+        (PublishFlags::ALLOW_MULTIPLE, AvahiPublishFlags::AVAHI_PUBLISH_ALLOW_MULTIPLE as c_int),
+        (PublishFlags::NO_REVERSE, AvahiPublishFlags::AVAHI_PUBLISH_NO_REVERSE as c_int),
+        (PublishFlags::NO_COOKIE, AvahiPublishFlags::AVAHI_PUBLISH_NO_COOKIE as c_int),
+        (PublishFlags::UPDATE, AvahiPublishFlags::AVAHI_PUBLISH_UPDATE as c_int),
+        (PublishFlags::USE_WIDE_AREA, AvahiPublishFlags::AVAHI_PUBLISH_USE_WIDE_AREA as c_int),
+        (PublishFlags::USE_MULTICAST, AvahiPublishFlags::AVAHI_PUBLISH_USE_MULTICAST as c_int),
+    ];
+
+    flag_pairs
+        .iter()
+        .filter(|&&(flag, _)| flags.contains(flag))
+        .fold(0, |avahi_flags, &(_, avahi_flag)| avahi_flags | avahi_flag)
+}
+
+/// Adds service described by `registration` (including its subtypes) to the entry
+/// group, entry group should be committed afterwards.
+fn add_service_entries(
+    entry_group: *mut AvahiEntryGroup,
+    registration: &ServiceRegistration,
+) -> Result<(), AdapterError> {
+    for interface in service_interfaces(registration) {
+        add_interface_service_entries(entry_group, registration, interface)?;
+    }
+
+    Ok(())
+}
+
+/// Returns interfaces service should be published on, `AVAHI_IF_UNSPEC` means all.
+fn service_interfaces(registration: &ServiceRegistration) -> Vec<c_int> {
+    if registration.interfaces.is_empty() {
+        vec![AvahiIfIndex::AVAHI_IF_UNSPEC as c_int]
+    } else {
+        registration.interfaces.clone()
+    }
+}
+
+/// Adds service and its subtypes published on the single `interface` to the entry group.
+fn add_interface_service_entries(
+    entry_group: *mut AvahiEntryGroup,
+    registration: &ServiceRegistration,
+    interface: c_int,
+) -> Result<(), AdapterError> {
+    let protocol = service_protocol_to_avahi_protocol(registration.protocol);
+    let flags = publish_flags_to_avahi_flags(registration.flags);
+
+    let name = service_field_to_c_string(Some(registration.name.clone()), "name")?;
+    let service_type = service_field_to_c_string(Some(registration.service_type.clone()), "type")?;
+    let domain = domain_to_c_string(registration.domain.as_deref())?;
+    let host = match registration.host {
+        Some(ref host) => Some(service_field_to_c_string(Some(host.clone()), "host")?),
+        None => None,
+    };
+    let domain_ptr = domain.as_ref().map_or(ptr::null(), |domain| domain.as_ptr());
+    let host_ptr = host.as_ref().map_or(ptr::null(), |host| host.as_ptr());
+
+    let txt = AvahiUtils::to_string_list(&registration.txt);
+
+    let result_code = unsafe {
+        avahi_entry_group_add_service_strlst(
+            entry_group,
+            interface,
+            protocol,
+            flags,
+            name.as_ptr(),
+            service_type.as_ptr(),
+            domain_ptr,
+            host_ptr,
+            registration.port,
+            txt,
+        )
+    };
+
+    unsafe { avahi_string_list_free(txt) };
+
+    if result_code != 0 {
+        return Err(From::from(AvahiError::from_error_code(result_code)));
+    }
+
+    for subtype in registration.full_subtypes() {
+        let subtype = service_field_to_c_string(Some(subtype), "subtype")?;
+        let result_code = unsafe {
+            avahi_entry_group_add_service_subtype(
+                entry_group,
+                interface,
+                protocol,
+                flags,
+                name.as_ptr(),
+                service_type.as_ptr(),
+                domain_ptr,
+                subtype.as_ptr(),
+            )
+        };
+
+        if result_code != 0 {
+            return Err(From::from(AvahiError::from_error_code(result_code)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds service entries to the entry group and commits it.
+fn commit_service_entries(
+    entry_group: *mut AvahiEntryGroup,
+    registration: &ServiceRegistration,
+) -> Result<(), AdapterError> {
+    add_service_entries(entry_group, registration)?;
+
+    let result_code = unsafe { avahi_entry_group_commit(entry_group) };
+    if result_code != 0 {
+        return Err(From::from(AvahiError::from_error_code(result_code)));
+    }
+
+    Ok(())
+}
+
+fn alternative_service_name(service_name: &str) -> Result<String, AdapterError> {
+    let original_service_name = service_field_to_c_string(Some(service_name.to_owned()), "name")?;
+
+    let alternative_service_name_ptr =
+        unsafe { avahi_alternative_service_name(original_service_name.as_ptr()) };
+
+    let alternative_service_name = AvahiUtils::to_owned_string(alternative_service_name_ptr);
+
+    unsafe { avahi_free(alternative_service_name_ptr as *mut c_void) };
+
+    alternative_service_name
+        .ok_or_else(|| AdapterError::Internal("Name is not available".to_owned()))
+}
+
+fn alternative_host_name(host_name: &str) -> Result<String, AdapterError> {
+    let original_host_name = name_to_c_string(host_name)?;
+
+    let alternative_host_name_ptr =
+        unsafe { avahi_alternative_host_name(original_host_name.as_ptr()) };
+
+    let alternative_host_name = AvahiUtils::to_owned_string(alternative_host_name_ptr);
+
+    unsafe { avahi_free(alternative_host_name_ptr as *mut c_void) };
+
+    alternative_host_name.ok_or_else(|| AdapterError::Internal("Name is not available".to_owned()))
+}
+
+/// Creates entry group handler that reports service state changes to `handler`. On
+/// collision service is re-registered under alternative name right away, in the event
+/// loop thread, the same way it's done in the Avahi examples.
+fn create_service_handler(
+    registration: Arc<Mutex<ServiceRegistration>>,
+    handler: ServiceEventHandler,
+) -> EntryGroupCallbackHandler {
+    Box::new(move |entry_group, state| {
+        let event = match state {
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_ESTABLISHED => {
+                ServiceEvent::Established(registration.lock().unwrap().name.clone())
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_COLLISION => {
+                let mut registration = registration.lock().unwrap();
+                let name = registration.name.clone();
+                let new_name = match alternative_service_name(&name) {
+                    Ok(new_name) => new_name,
+                    Err(error) => {
+                        handler(ServiceEvent::Failed(error));
+                        return;
+                    }
+                };
+
+                warn!("Service name collision, renaming {:?} to {:?}.", name, new_name);
+
+                registration.name = new_name.clone();
+
+                unsafe { avahi_entry_group_reset(entry_group) };
+
+                match commit_service_entries(entry_group, &registration) {
+                    Ok(()) => ServiceEvent::Collision { name, new_name },
+                    Err(error) => ServiceEvent::Failed(error),
+                }
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_FAILURE => {
+                let error_code =
+                    unsafe { avahi_client_errno(avahi_entry_group_get_client(entry_group)) };
+                ServiceEvent::Failed(From::from(AvahiError::from_error_code(error_code)))
+            }
+            _ => return,
+        };
+
+        handler(event);
+    })
+}
+
+/// Adds DNS record described by `registration` with already encoded `rdata` to the
+/// entry group and commits it.
+fn add_record_entry(
+    entry_group: *mut AvahiEntryGroup,
+    name: &CString,
+    registration: &RecordRegistration,
+    rdata: &[u8],
+) -> Result<(), AvahiError> {
+    let result_code = unsafe {
+        avahi_entry_group_add_record(
+            entry_group,
+            registration.interface,
+            service_protocol_to_avahi_protocol(registration.protocol),
+            publish_flags_to_avahi_flags(registration.flags),
+            name.as_ptr(),
+            registration.record_class,
+            registration.data.record_type().to_u16(),
+            registration.ttl,
+            rdata.as_ptr() as *const c_void,
+            rdata.len(),
+        )
+    };
+
+    if result_code != 0 {
+        return Err(AvahiError::from_error_code(result_code));
+    }
+
+    let result_code = unsafe { avahi_entry_group_commit(entry_group) };
+    if result_code != 0 {
+        return Err(AvahiError::from_error_code(result_code));
+    }
+
+    Ok(())
+}
+
+/// Creates entry group handler that reports published records state changes to
+/// `handler`. Arbitrary records can't be renamed, so collision is reported as failure.
+fn create_record_handler(
+    name: Arc<Mutex<String>>,
+    handler: PublishEventHandler,
+) -> EntryGroupCallbackHandler {
+    Box::new(move |entry_group, state| {
+        let name = name.lock().unwrap();
+        let error_code = match state {
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_ESTABLISHED => {
+                handler(PublishEvent::Established(name.clone()));
+                return;
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_COLLISION => {
+                warn!("Record name {:?} collided with another record.", *name);
+                AVAHI_ERR_COLLISION
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_FAILURE => unsafe {
+                avahi_client_errno(avahi_entry_group_get_client(entry_group))
+            },
+            _ => return,
+        };
 
-    for part in name_fqdn.split('.') {
-        rdata.push(part.len() as u8);
-        rdata.extend_from_slice(part.as_bytes());
+        let error = AvahiError::from_error_code(error_code);
+        handler(PublishEvent::Failed(From::from(error)));
+    })
+}
+
+/// Creates entry group handler that keeps alias `state` up to date and reports state
+/// changes to `handler`. Aliases aren't renamed, so collision is reported as failure.
+fn create_alias_handler(
+    name: String,
+    state: Arc<Mutex<AliasState>>,
+    handler: PublishEventHandler,
+) -> EntryGroupCallbackHandler {
+    Box::new(move |entry_group, entry_group_state| {
+        let (alias_state, error_code) = match entry_group_state {
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_ESTABLISHED => {
+                *state.lock().unwrap() = AliasState::Established;
+                handler(PublishEvent::Established(name.clone()));
+                return;
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_COLLISION => {
+                warn!("Host name alias {:?} collided with another record.", name);
+                (AliasState::Collision, AVAHI_ERR_COLLISION)
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_FAILURE => {
+                let error_code =
+                    unsafe { avahi_client_errno(avahi_entry_group_get_client(entry_group)) };
+                (AliasState::Failed, error_code)
+            }
+            _ => return,
+        };
+
+        *state.lock().unwrap() = alias_state;
+
+        let error = AvahiError::from_error_code(error_code);
+        handler(PublishEvent::Failed(From::from(error)));
+    })
+}
+
+/// Adds address record described by `registration` for the host `name` (it differs
+/// from the registered one after collisions) to the entry group and commits it.
+fn commit_address_entry(
+    entry_group: *mut AvahiEntryGroup,
+    name: &str,
+    registration: &AddressRegistration,
+) -> Result<(), AdapterError> {
+    let name = name_to_c_string(name)?;
+    let address = AvahiUtils::to_avahi_address(registration.address);
+
+    let result_code = unsafe {
+        avahi_entry_group_add_address(
+            entry_group,
+            registration.interface,
+            service_protocol_to_avahi_protocol(registration.protocol),
+            publish_flags_to_avahi_flags(registration.flags),
+            name.as_ptr(),
+            &address,
+        )
+    };
+
+    if result_code != 0 {
+        return Err(From::from(AvahiError::from_error_code(result_code)));
     }
 
-    // Push NULL byte.
-    rdata.push(0);
+    let result_code = unsafe { avahi_entry_group_commit(entry_group) };
+    if result_code != 0 {
+        return Err(From::from(AvahiError::from_error_code(result_code)));
+    }
 
-    rdata
+    Ok(())
+}
+
+/// Creates entry group handler that reports address records state changes to `handler`.
+/// On collision address is re-published under alternative host name right away, the
+/// same way it's done for services.
+fn create_address_handler(
+    name: Arc<Mutex<String>>,
+    registration: AddressRegistration,
+    handler: PublishEventHandler,
+) -> EntryGroupCallbackHandler {
+    Box::new(move |entry_group, state| {
+        let event = match state {
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_ESTABLISHED => {
+                PublishEvent::Established(name.lock().unwrap().clone())
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_COLLISION => {
+                let mut current_name = name.lock().unwrap();
+                let old_name = current_name.clone();
+                let new_name = match alternative_host_name(&old_name) {
+                    Ok(new_name) => new_name,
+                    Err(error) => {
+                        handler(PublishEvent::Failed(error));
+                        return;
+                    }
+                };
+
+                warn!(
+                    "Host name collision, renaming {:?} to {:?}.",
+                    old_name, new_name
+                );
+
+                *current_name = new_name.clone();
+
+                unsafe { avahi_entry_group_reset(entry_group) };
+
+                match commit_address_entry(entry_group, &new_name, &registration) {
+                    Ok(()) => PublishEvent::Collision {
+                        name: old_name,
+                        new_name,
+                    },
+                    Err(error) => PublishEvent::Failed(error),
+                }
+            }
+            AvahiEntryGroupState::AVAHI_ENTRY_GROUP_FAILURE => {
+                let error_code =
+                    unsafe { avahi_client_errno(avahi_entry_group_get_client(entry_group)) };
+                PublishEvent::Failed(From::from(AvahiError::from_error_code(error_code)))
+            }
+            _ => return,
+        };
+
+        handler(event);
+    })
 }
 
 impl AvahiAdapter {
@@ -66,7 +811,7 @@ impl AvahiAdapter {
     /// # Arguments
     ///
     /// * `poll` - Abstracted `AvahiPoll` object that we'd like to create client for.
-    fn create_client(&self, poll: *mut AvahiPoll) -> Result<(), AvahiError> {
+    fn create_client(&self, poll: *mut AvahiPoll) -> Result<*mut AvahiClient, AvahiError> {
         let mut client_error_code: i32 = 0;
 
         let sender = Box::new(self.client_channel.sender.clone());
@@ -86,22 +831,21 @@ impl AvahiAdapter {
             return Err(AvahiError::from_error_code(client_error_code));
         }
 
-        for message in self.client_channel.receiver.iter() {
+        for message in self.client_channel.receiver.lock().unwrap().iter() {
             if let AvahiClientState::AVAHI_CLIENT_S_RUNNING = message.state {
                 break;
             }
         }
 
-        self.client.set(Some(avahi_client));
-
         debug!("Client is created.");
-        Ok(())
+        Ok(avahi_client)
     }
 
     /// Initializes `AvahiClient` and `AvahiPoll` objects and runs polling. If client
     /// has been already initialized, this method does nothing.
     fn initialize(&self) -> Result<(), AvahiError> {
-        if self.client.get().is_some() {
+        let mut client = self.client.lock().unwrap();
+        if client.is_some() {
             return Ok(());
         }
 
@@ -116,24 +860,62 @@ impl AvahiAdapter {
 
         debug!("Threaded poll is created.");
 
-        self.create_client(abstracted_poll)?;
+        *client = Some(self.create_client(abstracted_poll)?);
 
         let result_code = unsafe { avahi_threaded_poll_start(threaded_poll) };
         if result_code != 0 {
             return Err(AvahiError::from_error_code(result_code));
         }
 
-        self.poll.set(Some(threaded_poll));
+        *self.poll.lock().unwrap() = Some(threaded_poll);
 
         Ok(())
     }
 
+    /// Returns client, it must have been created with `initialize` before.
+    fn client(&self) -> *mut AvahiClient {
+        self.client.lock().unwrap().unwrap()
+    }
+
+    /// Runs `f` with the threaded poll locked. Avahi objects must not be accessed
+    /// from outside of the event loop thread without holding this lock.
+    fn with_poll_lock<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        let poll = *self.poll.lock().unwrap();
+        match poll {
+            Some(poll) => {
+                unsafe { avahi_threaded_poll_lock(poll) };
+                let result = f();
+                unsafe { avahi_threaded_poll_unlock(poll) };
+                result
+            }
+            None => f(),
+        }
+    }
+
+    fn next_id(&self) -> usize {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Runs `f` for the registered service with the threaded poll locked.
+    fn with_service<T, E, F>(&self, service_id: usize, f: F) -> Result<T, AdapterError>
+    where
+        AdapterError: From<E>,
+        F: FnOnce(&ServiceEntry) -> Result<T, E>,
+    {
+        let services = self.services.lock().unwrap();
+        let service = services
+            .get(&service_id)
+            .ok_or_else(|| AdapterError::Internal("Service is not registered".to_owned()))?;
+
+        self.with_poll_lock(|| f(service)).map_err(From::from)
+    }
+
     fn destroy(&self) {
         debug!("Avahi adapter is going to be dropped.");
 
-        let client = self.client.get();
+        let client = *self.client.lock().unwrap();
         if client.is_some() {
-            let avahi_poll = self.poll.get().unwrap();
+            let avahi_poll = self.poll.lock().unwrap().unwrap();
             let avahi_client = client.unwrap();
 
             unsafe {
@@ -143,7 +925,7 @@ impl AvahiAdapter {
                 // Free memory from our custom userdata.
                 Box::from_raw((*avahi_client).userdata);
 
-                // This will remove service browser as well as resolver.
+                // This will remove service browsers as well as resolvers.
                 avahi_client_free(avahi_client);
                 debug!("Avahi client has been destroyed successfully.");
 
@@ -151,9 +933,18 @@ impl AvahiAdapter {
                 debug!("Avahi threaded poll has been destroyed successfully.");
             }
 
-            self.poll.set(None);
-            self.client.set(None);
-            self.service_browser.set(None);
+            *self.poll.lock().unwrap() = None;
+            *self.client.lock().unwrap() = None;
+            self.service_browsers.lock().unwrap().clear();
+            self.service_type_browsers.lock().unwrap().clear();
+            self.domain_browsers.lock().unwrap().clear();
+            self.record_browsers.lock().unwrap().clear();
+            self.service_resolvers.lock().unwrap().clear();
+            self.host_name_resolvers.lock().unwrap().clear();
+            self.address_resolvers.lock().unwrap().clear();
+            self.services.lock().unwrap().clear();
+            self.records.lock().unwrap().clear();
+            self.aliases.lock().unwrap().clear();
 
             debug!("Avahi adapter has been dropped successfully.");
         }
@@ -164,138 +955,494 @@ impl DiscoveryAdapter for AvahiAdapter {
     fn start_discovery(
         &self,
         service_type: &str,
-        listeners: DiscoveryListeners,
-    ) -> Result<(), AdapterError> {
-        debug!("Discovery started for the service: {}.", service_type);
+        options: &DiscoveryOptions,
+        handler: DiscoveryEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!(
+            "Discovery started for the service: {} ({:?}).",
+            service_type, options
+        );
+
+        let service_type = service_field_to_c_string(Some(service_type.to_owned()), "type")?;
+        let domain = domain_to_c_string(options.domain.as_deref())?;
 
         self.initialize()?;
 
-        let service_type = AvahiUtils::to_c_string(service_type.to_owned()).into_raw();
-        let sender = Box::new(self.service_browser_channel.sender.clone());
+        let client = self.client();
+        let browse_handler: BrowseCallbackHandler =
+            Box::new(move |service_browser, parameters| {
+                handler(browse_parameters_to_event(service_browser, parameters))
+            });
+        let browse_handler = Box::into_raw(Box::new(browse_handler));
 
-        let avahi_service_browser = unsafe {
+        let avahi_service_browser = self.with_poll_lock(|| unsafe {
             avahi_service_browser_new(
-                self.client.get().unwrap(),
-                AvahiIfIndex::AVAHI_IF_UNSPEC,
-                AvahiProtocol::AVAHI_PROTO_UNSPEC,
-                service_type,
-                ptr::null_mut(),
-                AvahiLookupFlags::AVAHI_LOOKUP_UNSPEC,
+                client,
+                options.interface,
+                service_protocol_to_avahi_protocol(options.protocol),
+                service_type.as_ptr(),
+                domain.as_ref().map_or(ptr::null(), |domain| domain.as_ptr()),
+                lookup_flags_to_avahi_flags(options.flags),
                 *Box::new(AvahiCallbacks::browse_callback),
-                Box::into_raw(sender) as *mut _ as *mut c_void,
+                browse_handler as *mut c_void,
             )
-        };
+        });
 
-        self.service_browser.set(Some(avahi_service_browser));
+        if avahi_service_browser.is_null() {
+            unsafe { drop(Box::from_raw(browse_handler)) };
 
-        for message in self.service_browser_channel.receiver.iter() {
-            if message.is_none() {
-                break;
-            }
+            let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+            error!("Failed to create service browser: {}", error);
+            return Err(From::from(error));
+        }
 
-            let parameters = message.unwrap();
-
-            match parameters.event {
-                AvahiBrowserEvent::AVAHI_BROWSER_NEW => {
-                    let service = ServiceInfo {
-                        address: None,
-                        domain: parameters.domain,
-                        host_name: None,
-                        interface: parameters.interface,
-                        name: parameters.name,
-                        port: 0,
-                        protocol: avahi_protocol_to_service_protocol(parameters.protocol),
-                        txt: None,
-                        type_name: parameters.service_type,
-                    };
-
-                    if listeners.on_service_discovered.is_some() {
-                        (*listeners.on_service_discovered.unwrap())(service);
-                    }
-                }
-                AvahiBrowserEvent::AVAHI_BROWSER_ALL_FOR_NOW => {
-                    if listeners.on_all_discovered.is_some() {
-                        (*listeners.on_all_discovered.unwrap())();
-                    }
-                }
-                AvahiBrowserEvent::AVAHI_BROWSER_FAILURE => {
-                    let error_code = unsafe { avahi_client_errno(self.client.get().unwrap()) };
-                    error!(
-                        "Service browser failed: {}",
-                        AvahiError::from_error_code(error_code)
-                    );
-                }
-                _ => {}
-            }
+        let browser_id = self.next_id();
+        self.service_browsers.lock().unwrap().insert(
+            browser_id,
+            ServiceBrowserEntry {
+                object: avahi_service_browser,
+                handler: browse_handler,
+            },
+        );
+
+        Ok(browser_id)
+    }
+
+    fn start_service_type_discovery(
+        &self,
+        options: &DiscoveryOptions,
+        handler: ServiceTypeEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!(
+            "Service type discovery started for the domain: {:?}.",
+            options.domain
+        );
+
+        let domain = domain_to_c_string(options.domain.as_deref())?;
+
+        self.initialize()?;
+
+        let client = self.client();
+        let browse_handler: ServiceTypeBrowseCallbackHandler =
+            Box::new(move |service_type_browser, parameters| {
+                handler(service_type_browse_parameters_to_event(
+                    service_type_browser,
+                    parameters,
+                ))
+            });
+        let browse_handler = Box::into_raw(Box::new(browse_handler));
+
+        let avahi_service_type_browser = self.with_poll_lock(|| unsafe {
+            avahi_service_type_browser_new(
+                client,
+                options.interface,
+                service_protocol_to_avahi_protocol(options.protocol),
+                domain.as_ref().map_or(ptr::null(), |domain| domain.as_ptr()),
+                lookup_flags_to_avahi_flags(options.flags),
+                *Box::new(AvahiCallbacks::service_type_browse_callback),
+                browse_handler as *mut c_void,
+            )
+        });
+
+        if avahi_service_type_browser.is_null() {
+            unsafe { drop(Box::from_raw(browse_handler)) };
+
+            let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+            error!("Failed to create service type browser: {}", error);
+            return Err(From::from(error));
         }
 
-        // Reconstruct string to properly free up memory.
-        unsafe { CString::from_raw(service_type) };
+        let browser_id = self.next_id();
+        self.service_type_browsers.lock().unwrap().insert(
+            browser_id,
+            ServiceTypeBrowserEntry {
+                object: avahi_service_type_browser,
+                handler: browse_handler,
+            },
+        );
 
-        Ok(())
+        Ok(browser_id)
+    }
+
+    fn start_domain_discovery(
+        &self,
+        domain_type: DomainType,
+        options: &DiscoveryOptions,
+        handler: DomainEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!(
+            "Domain discovery started for the domain {:?} ({:?}).",
+            options.domain, domain_type
+        );
+
+        let domain = domain_to_c_string(options.domain.as_deref())?;
+
+        self.initialize()?;
+
+        let client = self.client();
+        let browse_handler: DomainBrowseCallbackHandler =
+            Box::new(move |domain_browser, parameters| {
+                handler(domain_browse_parameters_to_event(domain_browser, parameters))
+            });
+        let browse_handler = Box::into_raw(Box::new(browse_handler));
+
+        let avahi_domain_browser = self.with_poll_lock(|| unsafe {
+            avahi_domain_browser_new(
+                client,
+                options.interface,
+                service_protocol_to_avahi_protocol(options.protocol),
+                domain.as_ref().map_or(ptr::null(), |domain| domain.as_ptr()),
+                domain_type_to_avahi_domain_browser_type(domain_type),
+                lookup_flags_to_avahi_flags(options.flags),
+                *Box::new(AvahiCallbacks::domain_browse_callback),
+                browse_handler as *mut c_void,
+            )
+        });
+
+        if avahi_domain_browser.is_null() {
+            unsafe { drop(Box::from_raw(browse_handler)) };
+
+            let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+            error!("Failed to create domain browser: {}", error);
+            return Err(From::from(error));
+        }
+
+        let browser_id = self.next_id();
+        self.domain_browsers.lock().unwrap().insert(
+            browser_id,
+            DomainBrowserEntry {
+                object: avahi_domain_browser,
+                handler: browse_handler,
+            },
+        );
+
+        Ok(browser_id)
+    }
+
+    fn start_record_discovery(
+        &self,
+        name: &str,
+        record_class: u16,
+        record_type: RecordType,
+        options: &DiscoveryOptions,
+        handler: RecordEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!(
+            "Record discovery started for {} (class {}, type {:?}).",
+            name, record_class, record_type
+        );
+
+        let name = CString::new(name)
+            .map_err(|_| AdapterError::Internal("Record name contains NUL byte".to_owned()))?;
+
+        self.initialize()?;
+
+        let client = self.client();
+        let browse_handler: RecordBrowseCallbackHandler =
+            Box::new(move |record_browser, parameters| {
+                handler(record_browse_parameters_to_event(record_browser, parameters))
+            });
+        let browse_handler = Box::into_raw(Box::new(browse_handler));
+
+        let avahi_record_browser = self.with_poll_lock(|| unsafe {
+            avahi_record_browser_new(
+                client,
+                options.interface,
+                service_protocol_to_avahi_protocol(options.protocol),
+                name.as_ptr(),
+                record_class,
+                record_type.to_u16(),
+                lookup_flags_to_avahi_flags(options.flags),
+                *Box::new(AvahiCallbacks::record_browse_callback),
+                browse_handler as *mut c_void,
+            )
+        });
+
+        if avahi_record_browser.is_null() {
+            unsafe { drop(Box::from_raw(browse_handler)) };
+
+            let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+            error!("Failed to create record browser: {}", error);
+            return Err(From::from(error));
+        }
+
+        let browser_id = self.next_id();
+        self.record_browsers.lock().unwrap().insert(
+            browser_id,
+            RecordBrowserEntry {
+                object: avahi_record_browser,
+                handler: browse_handler,
+            },
+        );
+
+        Ok(browser_id)
     }
 
-    fn resolve(&self, service: ServiceInfo, listeners: ResolveListeners) {
+    fn start_resolve(
+        &self,
+        service: ServiceInfo,
+        address_protocol: ServiceProtocol,
+        flags: LookupFlags,
+        handler: ResolveEventHandler,
+    ) -> Result<usize, AdapterError> {
         debug!("Resolution is requested for service: {:?}.", service);
 
-        let (tx, rx) = mpsc::channel::<ResolveCallbackParameters>();
+        let interface = service.interface;
+        let protocol = service_protocol_to_avahi_protocol(service.protocol);
+        let name = service_field_to_c_string(service.name, "name")?;
+        let service_type = service_field_to_c_string(service.type_name, "type")?;
+        // Default domain is used if domain is not specified.
+        let domain = match service.domain {
+            Some(domain) => Some(service_field_to_c_string(Some(domain), "domain")?),
+            None => None,
+        };
+
+        self.initialize()?;
+
+        let client = self.client();
+        let resolve_handler: ResolveCallbackHandler =
+            Box::new(move |service_resolver, parameters| {
+                handler(resolve_parameters_to_result(service_resolver, parameters))
+            });
+        let resolve_handler = Box::into_raw(Box::new(resolve_handler));
 
-        let avahi_service_resolver = unsafe {
+        let avahi_service_resolver = self.with_poll_lock(|| unsafe {
             avahi_service_resolver_new(
-                self.client.get().unwrap(),
-                service.interface,
-                service_protocol_to_avahi_protocol(service.protocol),
-                AvahiUtils::to_c_string(service.name.unwrap()).as_ptr(),
-                AvahiUtils::to_c_string(service.type_name.unwrap()).as_ptr(),
-                AvahiUtils::to_c_string(service.domain.unwrap()).as_ptr(),
-                AvahiProtocol::AVAHI_PROTO_UNSPEC,
-                AvahiLookupFlags::AVAHI_LOOKUP_UNSPEC,
+                client,
+                interface,
+                protocol,
+                name.as_ptr(),
+                service_type.as_ptr(),
+                domain.as_ref().map_or(ptr::null(), |domain| domain.as_ptr()),
+                service_protocol_to_avahi_protocol(address_protocol),
+                lookup_flags_to_avahi_flags(flags),
                 *Box::new(AvahiCallbacks::resolve_callback),
-                Box::into_raw(Box::new(tx)) as *mut _ as *mut c_void,
+                resolve_handler as *mut c_void,
             )
-        };
+        });
 
-        for message in rx.iter() {
-            if let AvahiResolverEvent::AVAHI_RESOLVER_FOUND = message.event {
-                let service = ServiceInfo {
-                    address: message.address,
-                    domain: message.domain,
-                    host_name: message.host_name,
-                    interface: message.interface,
-                    name: message.name,
-                    port: message.port,
-                    protocol: avahi_protocol_to_service_protocol(message.protocol),
-                    txt: message.txt,
-                    type_name: message.service_type,
-                };
+        if avahi_service_resolver.is_null() {
+            unsafe { drop(Box::from_raw(resolve_handler)) };
 
-                if listeners.on_service_resolved.is_some() {
-                    (*listeners.on_service_resolved.unwrap())(service);
-                }
+            let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+            error!("Failed to create service resolver: {}", error);
+            return Err(From::from(error));
+        }
 
-                break;
-            }
+        let resolver_id = self.next_id();
+        self.service_resolvers.lock().unwrap().insert(
+            resolver_id,
+            ServiceResolverEntry {
+                object: avahi_service_resolver,
+                handler: resolve_handler,
+            },
+        );
+
+        Ok(resolver_id)
+    }
+
+    fn stop_resolve(&self, resolver_id: usize) {
+        if let Some(resolver) = self.service_resolvers.lock().unwrap().remove(&resolver_id) {
+            self.with_poll_lock(|| {
+                unsafe { avahi_service_resolver_free(resolver.object) };
+                drop(resolver);
+            });
+
+            debug!("Avahi service resolver has been destroyed successfully.");
         }
+    }
 
-        unsafe {
-            avahi_service_resolver_free(avahi_service_resolver);
+    fn start_host_name_resolve(
+        &self,
+        host_name: &str,
+        address_protocol: ServiceProtocol,
+        options: &DiscoveryOptions,
+        handler: HostResolveEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!("Resolution is requested for host name: {}.", host_name);
+
+        let host_name = CString::new(host_name)
+            .map_err(|_| AdapterError::Internal("Host name contains NUL byte".to_owned()))?;
+
+        self.initialize()?;
+
+        let client = self.client();
+        let resolve_handler: HostNameResolveCallbackHandler =
+            Box::new(move |host_name_resolver, parameters| {
+                let client = unsafe { avahi_host_name_resolver_get_client(host_name_resolver) };
+                handler(host_name_resolve_parameters_to_result(client, parameters))
+            });
+        let resolve_handler = Box::into_raw(Box::new(resolve_handler));
+
+        let avahi_host_name_resolver = self.with_poll_lock(|| unsafe {
+            avahi_host_name_resolver_new(
+                client,
+                options.interface,
+                service_protocol_to_avahi_protocol(options.protocol),
+                host_name.as_ptr(),
+                service_protocol_to_avahi_protocol(address_protocol),
+                lookup_flags_to_avahi_flags(options.flags),
+                *Box::new(AvahiCallbacks::host_name_resolve_callback),
+                resolve_handler as *mut c_void,
+            )
+        });
+
+        if avahi_host_name_resolver.is_null() {
+            unsafe { drop(Box::from_raw(resolve_handler)) };
+
+            let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+            error!("Failed to create host name resolver: {}", error);
+            return Err(From::from(error));
         }
+
+        let resolver_id = self.next_id();
+        self.host_name_resolvers.lock().unwrap().insert(
+            resolver_id,
+            HostNameResolverEntry {
+                object: avahi_host_name_resolver,
+                handler: resolve_handler,
+            },
+        );
+
+        Ok(resolver_id)
     }
 
-    fn stop_discovery(&self) {
-        let service_browser = self.service_browser.get();
-        if service_browser.is_some() {
-            let avahi_service_browser = service_browser.unwrap();
-            unsafe {
-                // Free memory from our custom userdata.
-                Box::from_raw((*avahi_service_browser).userdata);
+    fn stop_host_name_resolve(&self, resolver_id: usize) {
+        if let Some(resolver) = self
+            .host_name_resolvers
+            .lock()
+            .unwrap()
+            .remove(&resolver_id)
+        {
+            self.with_poll_lock(|| {
+                unsafe { avahi_host_name_resolver_free(resolver.object) };
+                drop(resolver);
+            });
+
+            debug!("Avahi host name resolver has been destroyed successfully.");
+        }
+    }
 
-                avahi_service_browser_free(avahi_service_browser);
-                debug!("Avahi service browser has been destroyed successfully.");
-            }
+    fn start_address_resolve(
+        &self,
+        address: IpAddr,
+        options: &DiscoveryOptions,
+        handler: HostResolveEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!("Resolution is requested for address: {}.", address);
+
+        self.initialize()?;
+
+        let client = self.client();
+        let resolve_handler: AddressResolveCallbackHandler =
+            Box::new(move |address_resolver, parameters| {
+                let client = unsafe { avahi_address_resolver_get_client(address_resolver) };
+                handler(host_name_resolve_parameters_to_result(client, parameters))
+            });
+        let resolve_handler = Box::into_raw(Box::new(resolve_handler));
+
+        let address = AvahiUtils::to_avahi_address(address);
+        let avahi_address_resolver = self.with_poll_lock(|| unsafe {
+            avahi_address_resolver_new(
+                client,
+                options.interface,
+                service_protocol_to_avahi_protocol(options.protocol),
+                &address,
+                lookup_flags_to_avahi_flags(options.flags),
+                *Box::new(AvahiCallbacks::address_resolve_callback),
+                resolve_handler as *mut c_void,
+            )
+        });
+
+        if avahi_address_resolver.is_null() {
+            unsafe { drop(Box::from_raw(resolve_handler)) };
+
+            let error = AvahiError::from_error_code(unsafe { avahi_client_errno(client) });
+            error!("Failed to create address resolver: {}", error);
+            return Err(From::from(error));
+        }
+
+        let resolver_id = self.next_id();
+        self.address_resolvers.lock().unwrap().insert(
+            resolver_id,
+            AddressResolverEntry {
+                object: avahi_address_resolver,
+                handler: resolve_handler,
+            },
+        );
 
-            self.service_browser.set(None);
-            self.service_browser_channel.sender.send(None).unwrap();
+        Ok(resolver_id)
+    }
+
+    fn stop_address_resolve(&self, resolver_id: usize) {
+        if let Some(resolver) = self.address_resolvers.lock().unwrap().remove(&resolver_id) {
+            self.with_poll_lock(|| {
+                unsafe { avahi_address_resolver_free(resolver.object) };
+                drop(resolver);
+            });
+
+            debug!("Avahi address resolver has been destroyed successfully.");
+        }
+    }
+
+    fn stop_discovery(&self, browser_id: usize) {
+        if let Some(browser) = self.service_browsers.lock().unwrap().remove(&browser_id) {
+            self.with_poll_lock(|| {
+                unsafe { avahi_service_browser_free(browser.object) };
+                // Dropping handler closes the channel discovery events are delivered through.
+                drop(browser);
+            });
+
+            debug!("Avahi service browser has been destroyed successfully.");
+        }
+
+        if let Some(browser) = self
+            .service_type_browsers
+            .lock()
+            .unwrap()
+            .remove(&browser_id)
+        {
+            self.with_poll_lock(|| {
+                unsafe { avahi_service_type_browser_free(browser.object) };
+                drop(browser);
+            });
+
+            debug!("Avahi service type browser has been destroyed successfully.");
+        }
+
+        if let Some(browser) = self.domain_browsers.lock().unwrap().remove(&browser_id) {
+            self.with_poll_lock(|| {
+                unsafe { avahi_domain_browser_free(browser.object) };
+                drop(browser);
+            });
+
+            debug!("Avahi domain browser has been destroyed successfully.");
+        }
+
+        if let Some(browser) = self.record_browsers.lock().unwrap().remove(&browser_id) {
+            self.with_poll_lock(|| {
+                unsafe { avahi_record_browser_free(browser.object) };
+                drop(browser);
+            });
+
+            debug!("Avahi record browser has been destroyed successfully.");
+        }
+    }
+
+    fn stop_all_discovery(&self) {
+        let mut browser_ids: Vec<usize> = self
+            .service_browsers
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        browser_ids.extend(self.service_type_browsers.lock().unwrap().keys().cloned());
+        browser_ids.extend(self.domain_browsers.lock().unwrap().keys().cloned());
+        browser_ids.extend(self.record_browsers.lock().unwrap().keys().cloned());
+
+        for browser_id in browser_ids {
+            self.stop_discovery(browser_id);
         }
     }
 }
@@ -306,10 +1453,12 @@ impl HostAdapter for AvahiAdapter {
 
         self.initialize()?;
 
-        let host_name_ptr = unsafe { avahi_client_get_host_name(self.client.get().unwrap()) };
+        let client = self.client();
+        let host_name = self.with_poll_lock(|| {
+            AvahiUtils::to_owned_string(unsafe { avahi_client_get_host_name(client) })
+        });
 
-        AvahiUtils::to_owned_string(host_name_ptr)
-            .ok_or_else(|| AdapterError::Internal("Name is not available".to_owned()))
+        host_name.ok_or_else(|| AdapterError::Internal("Name is not available".to_owned()))
     }
 
     fn get_name_fqdn(&self) -> Result<String, AdapterError> {
@@ -317,11 +1466,12 @@ impl HostAdapter for AvahiAdapter {
 
         self.initialize()?;
 
-        let host_name_fqdn_ptr =
-            unsafe { avahi_client_get_host_name_fqdn(self.client.get().unwrap()) };
+        let client = self.client();
+        let host_name_fqdn = self.with_poll_lock(|| {
+            AvahiUtils::to_owned_string(unsafe { avahi_client_get_host_name_fqdn(client) })
+        });
 
-        AvahiUtils::to_owned_string(host_name_fqdn_ptr)
-            .ok_or_else(|| AdapterError::Internal("Name is not available".to_owned()))
+        host_name_fqdn.ok_or_else(|| AdapterError::Internal("Name is not available".to_owned()))
     }
 
     fn set_name(&self, host_name: &str) -> Result<String, AdapterError> {
@@ -335,17 +1485,18 @@ impl HostAdapter for AvahiAdapter {
             return Ok(host_name.to_owned());
         }
 
-        let client = self.client.get().unwrap();
-        let host_name = AvahiUtils::to_c_string(host_name.to_owned()).into_raw();
+        let client = self.client();
+        let host_name = name_to_c_string(host_name)?;
 
-        let result_code = unsafe { avahi_client_set_host_name(client, host_name) };
+        let result_code = self
+            .with_poll_lock(|| unsafe { avahi_client_set_host_name(client, host_name.as_ptr()) });
         if result_code != 0 {
             return Err(From::from(AvahiError::from_error_code(result_code)));
         }
 
         debug!("Waiting for the name to be applied.");
 
-        for message in self.client_channel.receiver.iter() {
+        for message in self.client_channel.receiver.lock().unwrap().iter() {
             if let AvahiClientState::AVAHI_CLIENT_S_RUNNING = message.state {
                 break;
             }
@@ -353,153 +1504,450 @@ impl HostAdapter for AvahiAdapter {
 
         debug!("Host name is successfully updated.");
 
-        // Reconstruct string to properly free up memory.
-        unsafe { CString::from_raw(host_name) };
-
         self.get_name()
     }
 
     fn is_valid_name(&self, host_name: &str) -> Result<bool, AdapterError> {
         debug!("Host name {:?} validation is requested.", host_name);
 
-        let host_name = AvahiUtils::to_c_string(host_name.to_owned()).into_raw();
+        let host_name = name_to_c_string(host_name)?;
 
-        let is_valid = unsafe { avahi_is_valid_host_name(host_name) } == 1;
+        let is_valid = unsafe { avahi_is_valid_host_name(host_name.as_ptr()) } == 1;
 
         debug!("Host name is valid: {:?}.", is_valid);
 
-        // Reconstruct string to properly free up memory.
-        unsafe { CString::from_raw(host_name) };
-
         Ok(is_valid)
     }
 
     fn get_alternative_name(&self, host_name: &str) -> Result<String, AdapterError> {
-        let original_host_name = AvahiUtils::to_c_string(host_name.to_owned()).into_raw();
+        alternative_host_name(host_name)
+    }
+
+    fn add_name_alias(
+        &self,
+        alias: &NameAlias,
+        handler: PublishEventHandler,
+    ) -> Result<(), AdapterError> {
+        debug!("Host name alias is requested: {:?}.", alias);
+
+        if alias.name.contains('\0') {
+            return Err(AdapterError::Internal(
+                "Alias name contains NUL byte".to_owned(),
+            ));
+        }
+
+        self.initialize()?;
+
+        let data = match alias.kind {
+            AliasKind::Cname => {
+                // Host can't be an alias of itself.
+                if alias.name == self.get_name()? {
+                    handler(PublishEvent::Established(alias.name.clone()));
+                    return Ok(());
+                }
+
+                RecordData::Cname(self.get_name_fqdn()?)
+            }
+            AliasKind::Address(IpAddr::V4(address)) => RecordData::A(address),
+            AliasKind::Address(IpAddr::V6(address)) => RecordData::Aaaa(address),
+        };
+
+        let registration = RecordRegistration::new(&alias.name, data).ttl(alias.ttl);
+        let rdata = registration.data.encode()?;
+        let c_name = name_to_c_string(&alias.name)?;
+
+        // Alias with the same name is replaced.
+        let _ = self.remove_name_alias(&alias.name);
+
+        let client = self.client();
+
+        let state = Arc::new(Mutex::new(AliasState::Registering));
+        let handler = Box::into_raw(Box::new(create_alias_handler(
+            alias.name.clone(),
+            state.clone(),
+            handler,
+        )));
+
+        let entry_group = self.with_poll_lock(|| {
+            let entry_group = unsafe {
+                avahi_entry_group_new(
+                    client,
+                    *Box::new(AvahiCallbacks::entry_group_callback),
+                    handler as *mut c_void,
+                )
+            };
+
+            if entry_group.is_null() {
+                let code = unsafe { avahi_client_errno(client) };
+                return Err(AvahiError::from_error_code(code));
+            }
+
+            if let Err(error) = add_record_entry(entry_group, &c_name, &registration, &rdata) {
+                error!("Failed to add and commit new entry group record: {}", error);
+                unsafe { avahi_entry_group_free(entry_group) };
+                return Err(error);
+            }
+
+            Ok(entry_group)
+        });
+
+        let entry_group = match entry_group {
+            Ok(entry_group) => entry_group,
+            Err(error) => {
+                unsafe { drop(Box::from_raw(handler)) };
+                return Err(From::from(error));
+            }
+        };
+
+        self.aliases.lock().unwrap().insert(
+            alias.name.clone(),
+            AliasEntry {
+                entry_group,
+                handler,
+                alias: alias.clone(),
+                state,
+            },
+        );
+
+        Ok(())
+    }
 
-        let alternative_host_name_ptr = unsafe { avahi_alternative_host_name(original_host_name) };
+    fn remove_name_alias(&self, name: &str) -> Result<(), AdapterError> {
+        debug!("Removal is requested for host name alias {:?}.", name);
 
-        let alternative_host_name = AvahiUtils::to_owned_string(alternative_host_name_ptr);
+        let alias = self
+            .aliases
+            .lock()
+            .unwrap()
+            .remove(name)
+            .ok_or_else(|| AdapterError::Internal("Alias is not registered".to_owned()))?;
 
-        unsafe { avahi_free(alternative_host_name_ptr as *mut c_void) };
+        self.with_poll_lock(|| {
+            unsafe { avahi_entry_group_free(alias.entry_group) };
+            drop(alias);
+        });
 
-        // Reconstruct string to properly free up memory.
-        unsafe { CString::from_raw(original_host_name) };
+        Ok(())
+    }
 
-        alternative_host_name
-            .ok_or_else(|| AdapterError::Internal("Name is not available".to_owned()))
+    fn get_name_aliases(&self) -> Vec<(NameAlias, AliasState)> {
+        self.aliases
+            .lock()
+            .unwrap()
+            .values()
+            .map(|alias| (alias.alias.clone(), *alias.state.lock().unwrap()))
+            .collect()
     }
 
-    fn add_name_alias(&self, host_name: &str) -> Result<(), AdapterError> {
+    fn get_alternative_service_name(&self, service_name: &str) -> Result<String, AdapterError> {
+        alternative_service_name(service_name)
+    }
+
+    fn register_service(
+        &self,
+        registration: &ServiceRegistration,
+        handler: ServiceEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!("Service registration is requested: {:?}.", registration);
+
         self.initialize()?;
+        let client = self.client();
+
+        let registration = Arc::new(Mutex::new(registration.clone()));
+        let handler = Box::into_raw(Box::new(create_service_handler(
+            registration.clone(),
+            handler,
+        )));
+
+        let entry_group = self.with_poll_lock(|| {
+            let entry_group = unsafe {
+                avahi_entry_group_new(
+                    client,
+                    *Box::new(AvahiCallbacks::entry_group_callback),
+                    handler as *mut c_void,
+                )
+            };
 
-        let current_host_name = self.get_name()?;
-        if host_name == current_host_name {
-            return Ok(());
-        }
+            if entry_group.is_null() {
+                let code = unsafe { avahi_client_errno(client) };
+                return Err(From::from(AvahiError::from_error_code(code)));
+            }
+
+            if let Err(error) = commit_service_entries(entry_group, &registration.lock().unwrap()) {
+                error!("Failed to add and commit new entry group record: {}", error);
+                unsafe { avahi_entry_group_free(entry_group) };
+                return Err(error);
+            }
 
-        let client = self.client.get().unwrap();
+            Ok(entry_group)
+        });
 
-        let entry_group = unsafe {
-            avahi_entry_group_new(
-                client,
-                *Box::new(AvahiCallbacks::entry_group_callback),
-                ptr::null_mut(),
-            )
+        let entry_group = match entry_group {
+            Ok(entry_group) => entry_group,
+            Err(error) => {
+                unsafe { drop(Box::from_raw(handler)) };
+                return Err(error);
+            }
         };
 
-        let rdata = &name_fqdn_to_cname_rdata(&self.get_name_fqdn()?);
+        let service_id = self.next_id();
+        self.services.lock().unwrap().insert(
+            service_id,
+            ServiceEntry {
+                entry_group,
+                handler,
+                registration,
+            },
+        );
 
-        let host_name = AvahiUtils::to_c_string(host_name.to_owned()).into_raw();
+        Ok(service_id)
+    }
 
-        let result_code = unsafe {
-            avahi_entry_group_add_record(
-                entry_group,
-                -1,
-                AvahiProtocol::AVAHI_PROTO_UNSPEC,
-                AvahiPublishFlags::AVAHI_PUBLISH_USE_MULTICAST,
-                host_name,
-                AvahiRecordClass::AVAHI_IN,
-                AvahiRecordType::AVAHI_CNAME,
-                60,
-                rdata.as_ptr() as *mut _,
-                rdata.len(),
-            )
-        };
+    fn update_service_txt(&self, service_id: usize, txt: &TxtRecord) -> Result<(), AdapterError> {
+        debug!("TXT record update is requested for service {}.", service_id);
+
+        self.with_service(service_id, |service| {
+            let mut registration = service.registration.lock().unwrap();
+
+            // Everything that can fail before Avahi is called is done upfront, so that
+            // invalid registration doesn't leave some interfaces updated.
+            let name = service_field_to_c_string(Some(registration.name.clone()), "name")?;
+            let service_type =
+                service_field_to_c_string(Some(registration.service_type.clone()), "type")?;
+            let domain = domain_to_c_string(registration.domain.as_deref())?;
+            let domain_ptr = domain.as_ref().map_or(ptr::null(), |domain| domain.as_ptr());
+            let protocol = service_protocol_to_avahi_protocol(registration.protocol);
+            let flags = publish_flags_to_avahi_flags(registration.flags);
+            let interfaces = service_interfaces(&registration);
+
+            let string_list = AvahiUtils::to_string_list(txt);
+            let mut updated_interfaces = Vec::new();
+            let mut failure = None;
+
+            for interface in interfaces {
+                let result_code = unsafe {
+                    avahi_entry_group_update_service_txt_strlst(
+                        service.entry_group,
+                        interface,
+                        protocol,
+                        flags,
+                        name.as_ptr(),
+                        service_type.as_ptr(),
+                        domain_ptr,
+                        string_list,
+                    )
+                };
 
-        if result_code != 0 {
-            let error = AvahiError::from_error_code(result_code);
-            error!("Failed to add new entry group record: {}", error);
-            return Err(From::from(error));
-        }
+                if result_code != 0 {
+                    failure = Some((interface, AvahiError::from_error_code(result_code)));
+                    break;
+                }
 
-        let result_code = unsafe { avahi_entry_group_commit(entry_group) };
-        if result_code != 0 {
-            let error = AvahiError::from_error_code(result_code);
-            error!("Failed to commit new entry group record: {}", error);
-            return Err(From::from(error));
-        }
+                updated_interfaces.push(interface);
+            }
 
-        // Reconstruct string to properly free up memory.
-        unsafe { CString::from_raw(host_name) };
+            unsafe { avahi_string_list_free(string_list) };
 
-        Ok(())
+            if let Some((interface, error)) = failure {
+                error!("Failed to update TXT record: {}", error);
+
+                return Err(AdapterError::AdapterFailure(format!(
+                    "Avahi - failed to update TXT record on interface {} ({}), it has been \
+                     updated on interfaces {:?} only",
+                    interface, error, updated_interfaces
+                )));
+            }
+
+            registration.txt = txt.clone();
+
+            Ok(())
+        })
+    }
+
+    fn reset_service(&self, service_id: usize) -> Result<(), AdapterError> {
+        debug!("Reset is requested for service {}.", service_id);
+
+        self.with_service(service_id, |service| {
+            let result_code = unsafe { avahi_entry_group_reset(service.entry_group) };
+            if result_code != 0 {
+                return Err(AvahiError::from_error_code(result_code));
+            }
+
+            Ok(())
+        })
+    }
+
+    fn republish_service(&self, service_id: usize) -> Result<(), AdapterError> {
+        debug!("Republish is requested for service {}.", service_id);
+
+        self.with_service(service_id, |service| {
+            commit_service_entries(service.entry_group, &service.registration.lock().unwrap())
+        })
+    }
+
+    fn unregister_service(&self, service_id: usize) {
+        debug!("Unregistration is requested for service {}.", service_id);
+
+        if let Some(service) = self.services.lock().unwrap().remove(&service_id) {
+            self.with_poll_lock(|| {
+                unsafe { avahi_entry_group_free(service.entry_group) };
+                drop(service);
+            });
+        }
     }
 
-    fn announce_service(&self, service_name: &str, service_type: &str, port: u16) -> Result<(), AdapterError> {
+    fn get_service_name(&self, service_id: usize) -> Result<String, AdapterError> {
+        self.services
+            .lock()
+            .unwrap()
+            .get(&service_id)
+            .map(|service| service.registration.lock().unwrap().name.clone())
+            .ok_or_else(|| AdapterError::Internal("Service is not registered".to_owned()))
+    }
+
+    fn publish_record(
+        &self,
+        registration: &RecordRegistration,
+        handler: PublishEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!("Record publishing is requested: {:?}.", registration);
+
+        let rdata = registration.data.encode()?;
+        let c_name = name_to_c_string(&registration.name)?;
+
         self.initialize()?;
-        let client = self.client.get().unwrap();
-        let entry_group = unsafe {
-            avahi_entry_group_new(
-                client,
-                *Box::new(AvahiCallbacks::entry_group_callback),
-                ptr::null_mut(),
-            )
+        let client = self.client();
+
+        let name = Arc::new(Mutex::new(registration.name.clone()));
+        let handler = Box::into_raw(Box::new(create_record_handler(name.clone(), handler)));
+
+        let entry_group = self.with_poll_lock(|| {
+            let entry_group = unsafe {
+                avahi_entry_group_new(
+                    client,
+                    *Box::new(AvahiCallbacks::entry_group_callback),
+                    handler as *mut c_void,
+                )
+            };
+
+            if entry_group.is_null() {
+                let code = unsafe { avahi_client_errno(client) };
+                return Err(AvahiError::from_error_code(code));
+            }
+
+            if let Err(error) = add_record_entry(entry_group, &c_name, registration, &rdata) {
+                error!("Failed to add and commit new entry group record: {}", error);
+                unsafe { avahi_entry_group_free(entry_group) };
+                return Err(error);
+            }
+
+            Ok(entry_group)
+        });
+
+        let entry_group = match entry_group {
+            Ok(entry_group) => entry_group,
+            Err(error) => {
+                unsafe { drop(Box::from_raw(handler)) };
+                return Err(From::from(error));
+            }
         };
 
-        if entry_group == 0 as _ {
-            let code = unsafe {
-                avahi_client_errno(client)
+        let record_id = self.next_id();
+        self.records.lock().unwrap().insert(
+            record_id,
+            RecordEntry {
+                entry_group,
+                handler,
+                name,
+            },
+        );
+
+        Ok(record_id)
+    }
+
+    fn publish_address(
+        &self,
+        registration: &AddressRegistration,
+        handler: PublishEventHandler,
+    ) -> Result<usize, AdapterError> {
+        debug!("Address publishing is requested: {:?}.", registration);
+
+        self.initialize()?;
+        let client = self.client();
+
+        let name = Arc::new(Mutex::new(registration.name.clone()));
+        let handler = Box::into_raw(Box::new(create_address_handler(
+            name.clone(),
+            registration.clone(),
+            handler,
+        )));
+
+        let entry_group = self.with_poll_lock(|| {
+            let entry_group = unsafe {
+                avahi_entry_group_new(
+                    client,
+                    *Box::new(AvahiCallbacks::entry_group_callback),
+                    handler as *mut c_void,
+                )
             };
-            let error = AvahiError::from_error_code(code);
-            return Err(From::from(error));
-        }
 
-        let service_name = AvahiUtils::to_c_string(service_name.to_owned()).into_raw();
-        let service_type = AvahiUtils::to_c_string(service_type.to_owned()).into_raw();
-        let result_code = unsafe {
-            avahi_entry_group_add_service(
-                entry_group,
-                AvahiIfIndex::AVAHI_IF_UNSPEC,
-                AvahiProtocol::AVAHI_PROTO_UNSPEC,
-                AvahiPublishFlags::AVAHI_PUBLISH_USE_MULTICAST,
-                service_name,
-                service_type,
-                ptr::null_mut(),
-                ptr::null_mut(),
-                port,
-                ptr::null_mut(),
-            )
+            if entry_group.is_null() {
+                let code = unsafe { avahi_client_errno(client) };
+                return Err(From::from(AvahiError::from_error_code(code)));
+            }
+
+            let result = commit_address_entry(entry_group, &name.lock().unwrap(), registration);
+            if let Err(error) = result {
+                error!(
+                    "Failed to add and commit new entry group address: {}",
+                    error
+                );
+                unsafe { avahi_entry_group_free(entry_group) };
+                return Err(error);
+            }
+
+            Ok(entry_group)
+        });
+
+        let entry_group = match entry_group {
+            Ok(entry_group) => entry_group,
+            Err(error) => {
+                unsafe { drop(Box::from_raw(handler)) };
+                return Err(error);
+            }
         };
 
-        if result_code != 0 {
-            let error = AvahiError::from_error_code(result_code);
-            error!("Failed to add a new entry group record: {}", error);
-            return Err(From::from(error));
-        }
+        let record_id = self.next_id();
+        self.records.lock().unwrap().insert(
+            record_id,
+            RecordEntry {
+                entry_group,
+                handler,
+                name,
+            },
+        );
 
-        let result_code = unsafe { avahi_entry_group_commit(entry_group) };
-        if result_code != 0 {
-            let error = AvahiError::from_error_code(result_code);
-            error!("Failed to commit new entry group record: {}", error);
-            return Err(From::from(error));
-        }
+        Ok(record_id)
+    }
 
-        // Reconstruct string to properly free up memory.
-        unsafe { CString::from_raw(service_type) };
-        unsafe { CString::from_raw(service_name) };
+    fn unpublish_record(&self, record_id: usize) {
+        debug!("Withdrawal is requested for record {}.", record_id);
 
-        Ok(())
+        if let Some(record) = self.records.lock().unwrap().remove(&record_id) {
+            self.with_poll_lock(|| {
+                unsafe { avahi_entry_group_free(record.entry_group) };
+                drop(record);
+            });
+        }
+    }
+
+    fn get_record_name(&self, record_id: usize) -> Result<String, AdapterError> {
+        self.records
+            .lock()
+            .unwrap()
+            .get(&record_id)
+            .map(|record| record.name.lock().unwrap().clone())
+            .ok_or_else(|| AdapterError::Internal("Record is not published".to_owned()))
     }
 }
 
@@ -513,23 +1961,29 @@ impl Adapter for AvahiAdapter {
     fn new() -> AvahiAdapter {
         let (client_sender, client_receiver) = mpsc::channel::<ClientCallbackParameters>();
 
-        let (service_browser_sender, service_browser_receiver) =
-            mpsc::channel::<Option<BrowseCallbackParameters>>();
-
         AvahiAdapter {
-            poll: Cell::new(None),
+            poll: Mutex::new(None),
 
-            client: Cell::new(None),
+            client: Mutex::new(None),
             client_channel: Channel {
-                receiver: client_receiver,
+                receiver: Mutex::new(client_receiver),
                 sender: client_sender,
             },
 
-            service_browser: Cell::new(None),
-            service_browser_channel: Channel {
-                receiver: service_browser_receiver,
-                sender: service_browser_sender,
-            },
+            service_browsers: Mutex::new(HashMap::new()),
+            service_type_browsers: Mutex::new(HashMap::new()),
+            domain_browsers: Mutex::new(HashMap::new()),
+            record_browsers: Mutex::new(HashMap::new()),
+
+            service_resolvers: Mutex::new(HashMap::new()),
+            host_name_resolvers: Mutex::new(HashMap::new()),
+            address_resolvers: Mutex::new(HashMap::new()),
+
+            services: Mutex::new(HashMap::new()),
+            records: Mutex::new(HashMap::new()),
+            aliases: Mutex::new(HashMap::new()),
+
+            next_id: AtomicUsize::new(0),
         }
     }
 }
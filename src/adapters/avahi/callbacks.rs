@@ -1,13 +1,51 @@
-use libc::{c_char, c_int, c_void};
+//! Callbacks that Avahi invokes on its event loop thread with the poll lock held. Every
+//! callback except `client_callback` gets pointer to its `*CallbackHandler` as
+//! `userdata`, decodes raw parameters and passes them to that handler.
 
+use libc::{c_char, c_int, c_void, size_t};
+
+use std::net::IpAddr;
+use std::slice;
 use std::sync::mpsc;
 
 use bindings::avahi::*;
 
 use adapters::avahi::utils::*;
+use discovery::txt_record::TxtRecord;
 
 pub struct AvahiCallbacks;
 
+/// Handler for `browse_callback`.
+pub type BrowseCallbackHandler =
+    Box<dyn Fn(*mut AvahiServiceBrowser, BrowseCallbackParameters) + Send>;
+
+/// Handler for `service_type_browse_callback`.
+pub type ServiceTypeBrowseCallbackHandler =
+    Box<dyn Fn(*mut AvahiServiceTypeBrowser, ServiceTypeBrowseCallbackParameters) + Send>;
+
+/// Handler for `domain_browse_callback`.
+pub type DomainBrowseCallbackHandler =
+    Box<dyn Fn(*mut AvahiDomainBrowser, DomainBrowseCallbackParameters) + Send>;
+
+/// Handler for `resolve_callback`.
+pub type ResolveCallbackHandler =
+    Box<dyn Fn(*mut AvahiServiceResolver, ResolveCallbackParameters) + Send>;
+
+/// Handler for `host_name_resolve_callback`.
+pub type HostNameResolveCallbackHandler =
+    Box<dyn Fn(*mut AvahiHostNameResolver, HostNameResolveCallbackParameters) + Send>;
+
+/// Handler for `address_resolve_callback`.
+pub type AddressResolveCallbackHandler =
+    Box<dyn Fn(*mut AvahiAddressResolver, HostNameResolveCallbackParameters) + Send>;
+
+/// Handler for `record_browse_callback`.
+pub type RecordBrowseCallbackHandler =
+    Box<dyn Fn(*mut AvahiRecordBrowser, RecordBrowseCallbackParameters) + Send>;
+
+/// Handler for `entry_group_callback`.
+pub type EntryGroupCallbackHandler = Box<dyn Fn(*mut AvahiEntryGroup, AvahiEntryGroupState) + Send>;
+
 #[derive(Debug)]
 pub struct ClientCallbackParameters {
     pub state: AvahiClientState,
@@ -21,13 +59,30 @@ pub struct BrowseCallbackParameters {
     pub name: Option<String>,
     pub service_type: Option<String>,
     pub domain: Option<String>,
-    pub flags: AvahiLookupResultFlags,
+    pub flags: c_int,
+}
+
+#[derive(Debug)]
+pub struct ServiceTypeBrowseCallbackParameters {
+    pub event: AvahiBrowserEvent,
+    pub interface: i32,
+    pub protocol: AvahiProtocol,
+    pub service_type: Option<String>,
+    pub domain: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct DomainBrowseCallbackParameters {
+    pub event: AvahiBrowserEvent,
+    pub interface: i32,
+    pub protocol: AvahiProtocol,
+    pub domain: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct ResolveCallbackParameters {
     pub event: AvahiResolverEvent,
-    pub address: Option<String>,
+    pub address: Option<IpAddr>,
     pub interface: i32,
     pub port: u16,
     pub protocol: AvahiProtocol,
@@ -35,8 +90,28 @@ pub struct ResolveCallbackParameters {
     pub service_type: Option<String>,
     pub domain: Option<String>,
     pub host_name: Option<String>,
-    pub txt: Option<String>,
-    pub flags: AvahiLookupResultFlags,
+    pub txt: Option<TxtRecord>,
+    pub flags: c_int,
+}
+
+#[derive(Debug)]
+pub struct HostNameResolveCallbackParameters {
+    pub event: AvahiResolverEvent,
+    pub interface: i32,
+    pub protocol: AvahiProtocol,
+    pub host_name: Option<String>,
+    pub address: Option<IpAddr>,
+}
+
+#[derive(Debug)]
+pub struct RecordBrowseCallbackParameters {
+    pub event: AvahiBrowserEvent,
+    pub interface: i32,
+    pub protocol: AvahiProtocol,
+    pub name: Option<String>,
+    pub record_class: u16,
+    pub record_type: u16,
+    pub data: Vec<u8>,
 }
 
 impl AvahiCallbacks {
@@ -59,7 +134,6 @@ impl AvahiCallbacks {
         Box::into_raw(sender);
     }
 
-    #[allow(unused_variables)]
     pub extern "C" fn browse_callback(
         service_browser: *const AvahiServiceBrowser,
         interface: c_int,
@@ -68,7 +142,7 @@ impl AvahiCallbacks {
         name: *const c_char,
         service_type: *const c_char,
         domain: *const c_char,
-        flags: AvahiLookupResultFlags,
+        flags: c_int,
         userdata: *const c_void,
     ) {
         let parameters = BrowseCallbackParameters {
@@ -83,18 +157,58 @@ impl AvahiCallbacks {
 
         debug!("Service state has changed: {:?}.", parameters);
 
-        let sender: Box<mpsc::Sender<Option<BrowseCallbackParameters>>> =
-            unsafe { Box::from_raw(userdata as *mut _) };
+        let handler = unsafe { &*(userdata as *const BrowseCallbackHandler) };
+        handler(service_browser as *mut _, parameters);
+    }
+
+    pub extern "C" fn service_type_browse_callback(
+        service_type_browser: *const AvahiServiceTypeBrowser,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        event: AvahiBrowserEvent,
+        service_type: *const c_char,
+        domain: *const c_char,
+        _flags: c_int,
+        userdata: *const c_void,
+    ) {
+        let parameters = ServiceTypeBrowseCallbackParameters {
+            event,
+            interface,
+            protocol,
+            service_type: AvahiUtils::to_owned_string(service_type),
+            domain: AvahiUtils::to_owned_string(domain),
+        };
 
-        sender.send(Some(parameters)).unwrap();
+        debug!("Service type state has changed: {:?}.", parameters);
 
-        // Leak pointer to the sender so that it can be reused later.
-        Box::into_raw(sender);
+        let handler = unsafe { &*(userdata as *const ServiceTypeBrowseCallbackHandler) };
+        handler(service_type_browser as *mut _, parameters);
+    }
+
+    pub extern "C" fn domain_browse_callback(
+        domain_browser: *const AvahiDomainBrowser,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        event: AvahiBrowserEvent,
+        domain: *const c_char,
+        _flags: c_int,
+        userdata: *const c_void,
+    ) {
+        let parameters = DomainBrowseCallbackParameters {
+            event,
+            interface,
+            protocol,
+            domain: AvahiUtils::to_owned_string(domain),
+        };
+
+        debug!("Domain state has changed: {:?}.", parameters);
+
+        let handler = unsafe { &*(userdata as *const DomainBrowseCallbackHandler) };
+        handler(domain_browser as *mut _, parameters);
     }
 
-    #[allow(unused_variables)]
     pub extern "C" fn resolve_callback(
-        r: *const AvahiServiceResolver,
+        service_resolver: *const AvahiServiceResolver,
         interface: c_int,
         protocol: AvahiProtocol,
         event: AvahiResolverEvent,
@@ -105,7 +219,7 @@ impl AvahiCallbacks {
         address: *const AvahiAddress,
         port: u16,
         txt: *mut AvahiStringList,
-        flags: AvahiLookupResultFlags,
+        flags: c_int,
         userdata: *const c_void,
     ) {
         let parameters = ResolveCallbackParameters {
@@ -124,18 +238,105 @@ impl AvahiCallbacks {
 
         debug!("Service resolution state has changed: {:?}.", parameters);
 
-        let sender: Box<mpsc::Sender<ResolveCallbackParameters>> =
-            unsafe { Box::from_raw(userdata as *mut _) };
+        let handler = unsafe { &*(userdata as *const ResolveCallbackHandler) };
+        handler(service_resolver as *mut _, parameters);
+    }
 
-        sender.send(parameters).unwrap();
+    pub extern "C" fn host_name_resolve_callback(
+        host_name_resolver: *const AvahiHostNameResolver,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        event: AvahiResolverEvent,
+        host_name: *const c_char,
+        address: *const AvahiAddress,
+        _flags: c_int,
+        userdata: *const c_void,
+    ) {
+        let parameters = HostNameResolveCallbackParameters {
+            event,
+            interface,
+            protocol,
+            host_name: AvahiUtils::to_owned_string(host_name),
+            address: AvahiUtils::parse_address(address),
+        };
+
+        debug!("Host name resolution state has changed: {:?}.", parameters);
+
+        let handler = unsafe { &*(userdata as *const HostNameResolveCallbackHandler) };
+        handler(host_name_resolver as *mut _, parameters);
+    }
+
+    pub extern "C" fn address_resolve_callback(
+        address_resolver: *const AvahiAddressResolver,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        event: AvahiResolverEvent,
+        address: *const AvahiAddress,
+        host_name: *const c_char,
+        _flags: c_int,
+        userdata: *const c_void,
+    ) {
+        let parameters = HostNameResolveCallbackParameters {
+            event,
+            interface,
+            protocol,
+            host_name: AvahiUtils::to_owned_string(host_name),
+            address: AvahiUtils::parse_address(address),
+        };
+
+        debug!("Address resolution state has changed: {:?}.", parameters);
+
+        let handler = unsafe { &*(userdata as *const AddressResolveCallbackHandler) };
+        handler(address_resolver as *mut _, parameters);
+    }
+
+    pub extern "C" fn record_browse_callback(
+        record_browser: *const AvahiRecordBrowser,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        event: AvahiBrowserEvent,
+        name: *const c_char,
+        record_class: u16,
+        record_type: u16,
+        data: *const c_void,
+        size: size_t,
+        _flags: c_int,
+        userdata: *const c_void,
+    ) {
+        let data = if data.is_null() {
+            vec![]
+        } else {
+            unsafe { slice::from_raw_parts(data as *const u8, size) }.to_vec()
+        };
+
+        let parameters = RecordBrowseCallbackParameters {
+            event,
+            interface,
+            protocol,
+            name: AvahiUtils::to_owned_string(name),
+            record_class,
+            record_type,
+            data,
+        };
+
+        debug!("Record state has changed: {:?}.", parameters);
+
+        let handler = unsafe { &*(userdata as *const RecordBrowseCallbackHandler) };
+        handler(record_browser as *mut _, parameters);
     }
 
-    #[allow(unused_variables)]
     pub extern "C" fn entry_group_callback(
         group: *const AvahiEntryGroup,
         state: AvahiEntryGroupState,
         userdata: *const c_void,
     ) {
         debug!("Entry group state has changed to {:?}.", state);
+
+        if userdata.is_null() {
+            return;
+        }
+
+        let handler = unsafe { &*(userdata as *const EntryGroupCallbackHandler) };
+        handler(group as *mut _, state);
     }
 }
@@ -1,16 +1,15 @@
-use libc::{c_char, c_void};
+use libc::{c_char, c_int};
 use std::ffi::CStr;
-use std::ffi::CString;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ptr;
+use std::slice;
 
 use bindings::avahi::*;
+use discovery::txt_record::*;
 
 pub struct AvahiUtils;
 
 impl AvahiUtils {
-    pub fn to_c_string(r_string: String) -> CString {
-        CString::new(r_string).unwrap()
-    }
-
     pub fn to_owned_string(c_string: *const c_char) -> Option<String> {
         if c_string.is_null() {
             None
@@ -23,30 +22,92 @@ impl AvahiUtils {
         }
     }
 
-    pub fn parse_address(address: *const AvahiAddress) -> Option<String> {
+    /// Decodes `AvahiAddress` into `IpAddr`, returns `None` for unknown address families.
+    pub fn parse_address(address: *const AvahiAddress) -> Option<IpAddr> {
         if address.is_null() {
-            None
+            return None;
+        }
+
+        let address = unsafe { &*address };
+
+        if address.proto == AvahiProtocol::AVAHI_PROTO_INET as c_int {
+            // Address is stored in network byte order.
+            let octets = unsafe { address.data.ipv4.address }.to_ne_bytes();
+            Some(IpAddr::V4(Ipv4Addr::from(octets)))
+        } else if address.proto == AvahiProtocol::AVAHI_PROTO_INET6 as c_int {
+            Some(IpAddr::V6(Ipv6Addr::from(unsafe {
+                address.data.ipv6.address
+            })))
         } else {
-            let address_vector = Vec::with_capacity(AVAHI_ADDRESS_STR_MAX);
-            unsafe {
-                avahi_address_snprint(address_vector.as_ptr(), AVAHI_ADDRESS_STR_MAX, address)
-            };
+            None
+        }
+    }
 
-            AvahiUtils::to_owned_string(address_vector.as_ptr())
+    /// Encodes `IpAddr` into `AvahiAddress`, it's the opposite of `parse_address`.
+    pub fn to_avahi_address(address: IpAddr) -> AvahiAddress {
+        match address {
+            IpAddr::V4(address) => AvahiAddress {
+                proto: AvahiProtocol::AVAHI_PROTO_INET as c_int,
+                data: AvahiAddressData {
+                    ipv4: AvahiIPv4Address {
+                        address: u32::from_ne_bytes(address.octets()),
+                    },
+                },
+            },
+            IpAddr::V6(address) => AvahiAddress {
+                proto: AvahiProtocol::AVAHI_PROTO_INET6 as c_int,
+                data: AvahiAddressData {
+                    ipv6: AvahiIPv6Address {
+                        address: address.octets(),
+                    },
+                },
+            },
         }
     }
 
-    pub fn parse_txt(txt: *mut AvahiStringList) -> Option<String> {
+    pub fn parse_txt(txt: *mut AvahiStringList) -> Option<TxtRecord> {
         if txt.is_null() {
-            None
-        } else {
-            unsafe {
-                let txt_pointer = avahi_string_list_to_string(txt);
-                let txt = AvahiUtils::to_owned_string(txt_pointer);
-                avahi_free(txt_pointer as *mut c_void);
+            return None;
+        }
 
-                txt
-            }
+        let mut entries = Vec::new();
+        let mut item = txt;
+
+        while !item.is_null() {
+            let bytes = unsafe {
+                slice::from_raw_parts(
+                    avahi_string_list_get_text(item),
+                    avahi_string_list_get_size(item),
+                )
+            };
+
+            entries.extend(TxtEntry::from_bytes(bytes));
+            item = unsafe { avahi_string_list_get_next(item) };
+        }
+
+        // Avahi stores string list items in reverse order, so we reverse them back
+        // the same way `avahi_string_list_to_string` does.
+        let mut txt_record = TxtRecord::new();
+        for entry in entries.into_iter().rev() {
+            txt_record.push(entry);
+        }
+
+        Some(txt_record)
+    }
+
+    /// Converts TXT record into `AvahiStringList`, result should be freed with
+    /// `avahi_string_list_free`. Returns NULL for an empty record.
+    pub fn to_string_list(txt: &TxtRecord) -> *mut AvahiStringList {
+        let mut string_list: *mut AvahiStringList = ptr::null_mut();
+
+        // Avahi prepends new items, so we add entries in reverse order to preserve it.
+        for entry in txt.iter().rev() {
+            let bytes = entry.to_bytes();
+            string_list = unsafe {
+                avahi_string_list_add_arbitrary(string_list, bytes.as_ptr(), bytes.len())
+            };
         }
+
+        string_list
     }
 }
@@ -1,10 +1,12 @@
 use std::error::Error as StdError;
 use std::fmt;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Error {
     AdapterFailure(String),
     Internal(String),
+    /// Operation hasn't completed within the requested time.
+    Timeout(String),
 }
 
 impl fmt::Display for Error {
@@ -12,6 +14,7 @@ impl fmt::Display for Error {
         match *self {
             Error::AdapterFailure(ref message) => f.write_str(message),
             Error::Internal(ref message) => f.write_str(message),
+            Error::Timeout(ref message) => f.write_str(message),
         }
     }
 }
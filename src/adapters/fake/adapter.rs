@@ -1,67 +1,378 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
 use adapters::adapter::*;
 use adapters::errors::Error;
 use discovery::discovery_manager::*;
+use discovery::discovery_options::DiscoveryOptions;
+use discovery::dns_record::{RecordData, RecordType, CLASS_IN};
+use discovery::lookup_flags::{LookupFlags, LookupResultFlags};
+use discovery::service::DiscoveredService;
+use discovery::txt_record::TxtRecord;
+use host::address_registration::AddressRegistration;
+use host::host_manager::{PublishEvent, ServiceEvent};
+use host::name_alias::{AliasState, NameAlias};
+use host::record_registration::RecordRegistration;
+use host::service_registration::ServiceRegistration;
+
+/// Adapter that doesn't touch the network, it's used on platforms real mDNS adapter
+/// doesn't support. With `test-util` feature enabled it can be passed to
+/// `DiscoveryManager::with_fake_adapter` and `HostManager::with_fake_adapter` to run
+/// code that uses managers without daemon.
+/// Clones share the same state, so a clone can be kept to add and remove fake services
+/// while the manager owns the adapter.
+#[derive(Clone)]
+pub struct FakeAdapter {
+    /// Table that host names are resolved with, see `add_host`.
+    hosts: Arc<Mutex<HashMap<String, Vec<IpAddr>>>>,
+    /// Services advertised by other hosts, see `add_service`.
+    remote_services: Arc<Mutex<Vec<RemoteService>>>,
+    /// Running service browsers that are notified about `remote_services` changes.
+    browsers: Arc<Mutex<HashMap<usize, FakeBrowser>>>,
+    /// Handlers of the resolvers that can't find service address, they are kept until
+    /// resolution is stopped, so that the caller's timeout applies.
+    pending_resolvers: Arc<Mutex<HashMap<usize, ResolveEventHandler>>>,
+    services: Arc<Mutex<HashMap<usize, ServiceRegistration>>>,
+    /// Names of the published records.
+    records: Arc<Mutex<HashMap<usize, String>>>,
+    aliases: Arc<Mutex<HashMap<String, NameAlias>>>,
+    next_id: Arc<AtomicUsize>,
+}
+
+/// Service advertised by another host or by another process of the local host.
+struct RemoteService {
+    name: String,
+    service_type: String,
+    flags: LookupResultFlags,
+}
+
+struct FakeBrowser {
+    service_type: String,
+    domain: String,
+    protocol: ServiceProtocol,
+    handler: DiscoveryEventHandler,
+}
 
-pub struct FakeAdapter;
+impl FakeBrowser {
+    fn service(&self, name: &str, flags: LookupResultFlags) -> DiscoveredService {
+        DiscoveredService {
+            name: name.to_owned(),
+            type_name: self.service_type.clone(),
+            domain: self.domain.clone(),
+            interface: 1,
+            protocol: self.protocol,
+            flags,
+        }
+    }
+}
 
 impl DiscoveryAdapter for FakeAdapter {
     fn start_discovery(
         &self,
         service_type: &str,
-        listeners: DiscoveryListeners,
-    ) -> Result<(), Error> {
+        options: &DiscoveryOptions,
+        handler: DiscoveryEventHandler,
+    ) -> Result<usize, Error> {
+        FakeAdapter::print_warning();
+
+        let browser_id = self.next_id();
+
+        // Fake services are available on the first interface over any protocol.
+        if options.interface == -1 || options.interface == 1 {
+            let browser = FakeBrowser {
+                service_type: service_type.to_owned(),
+                domain: options.domain.clone().unwrap_or_else(|| "local".to_owned()),
+                protocol: match options.protocol {
+                    ServiceProtocol::Unspecified => ServiceProtocol::IPv4,
+                    protocol => protocol,
+                },
+                handler,
+            };
+
+            for service in self.remote_services.lock().unwrap().iter() {
+                if service.service_type == browser.service_type {
+                    let service = browser.service(&service.name, service.flags);
+                    (browser.handler)(DiscoveryEvent::ServiceDiscovered(service));
+                }
+            }
+
+            for registration in self.services.lock().unwrap().values() {
+                if registration.service_type == browser.service_type {
+                    let service = browser.service(&registration.name, own_service_flags());
+                    (browser.handler)(DiscoveryEvent::ServiceDiscovered(service));
+                }
+            }
+
+            (browser.handler)(DiscoveryEvent::CacheExhausted);
+            (browser.handler)(DiscoveryEvent::AllDiscovered);
+
+            // Browser is kept until discovery is stopped, so that services added or
+            // removed afterwards are reported too.
+            self.browsers.lock().unwrap().insert(browser_id, browser);
+        } else {
+            handler(DiscoveryEvent::CacheExhausted);
+            handler(DiscoveryEvent::AllDiscovered);
+
+            // There is nothing to discover on other interfaces, so handler is dropped
+            // right away and discovery session ends once events above are received.
+        }
+
+        Ok(browser_id)
+    }
+
+    fn start_service_type_discovery(
+        &self,
+        options: &DiscoveryOptions,
+        handler: ServiceTypeEventHandler,
+    ) -> Result<usize, Error> {
         FakeAdapter::print_warning();
 
-        if listeners.on_service_discovered.is_some() {
-            (*listeners.on_service_discovered.unwrap())(ServiceInfo {
-                address: None,
-                domain: Some(format!("local")),
-                host_name: None,
+        // Types of the remote and registered services are reported, just like Avahi
+        // reports types of the services it has in the cache and publishes itself.
+        let mut service_types: Vec<String> = self
+            .remote_services
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|service| service.service_type.clone())
+            .collect();
+        service_types.extend(
+            self.services
+                .lock()
+                .unwrap()
+                .values()
+                .map(|registration| registration.service_type.clone()),
+        );
+        service_types.sort();
+        service_types.dedup();
+
+        if options.interface == -1 || options.interface == 1 {
+            for service_type in service_types {
+                handler(ServiceTypeEvent::ServiceTypeDiscovered(ServiceTypeInfo {
+                    service_type,
+                    domain: options.domain.as_deref().unwrap_or("local").to_owned(),
+                    interface: 1,
+                    protocol: ServiceProtocol::IPv4,
+                }));
+            }
+        }
+
+        handler(ServiceTypeEvent::CacheExhausted);
+        handler(ServiceTypeEvent::AllDiscovered);
+
+        Ok(self.next_id())
+    }
+
+    fn start_domain_discovery(
+        &self,
+        domain_type: DomainType,
+        options: &DiscoveryOptions,
+        handler: DomainEventHandler,
+    ) -> Result<usize, Error> {
+        FakeAdapter::print_warning();
+
+        if options.interface == -1 || options.interface == 1 {
+            debug!("Reporting link-local domain as {:?} domain.", domain_type);
+
+            handler(DomainEvent::DomainDiscovered(DomainInfo {
+                domain: "local".to_owned(),
                 interface: 1,
-                name: Some(format!("fake")),
-                port: 0,
                 protocol: ServiceProtocol::IPv4,
-                txt: None,
-                type_name: Some(service_type.to_string()),
-            });
+            }));
         }
 
-        if listeners.on_all_discovered.is_some() {
-            (*listeners.on_all_discovered.unwrap())();
+        handler(DomainEvent::CacheExhausted);
+        handler(DomainEvent::AllDiscovered);
+
+        Ok(self.next_id())
+    }
+
+    fn start_record_discovery(
+        &self,
+        name: &str,
+        record_class: u16,
+        record_type: RecordType,
+        _options: &DiscoveryOptions,
+        handler: RecordEventHandler,
+    ) -> Result<usize, Error> {
+        FakeAdapter::print_warning();
+
+        // Only address records of the known hosts are reported.
+        let addresses = match self.hosts.lock().unwrap().get(name) {
+            Some(addresses) if record_class == CLASS_IN => addresses.clone(),
+            _ => vec![],
+        };
+
+        for address in addresses {
+            let (data, protocol) = match (address, record_type) {
+                (IpAddr::V4(address), RecordType::A) => {
+                    (RecordData::A(address), ServiceProtocol::IPv4)
+                }
+                (IpAddr::V6(address), RecordType::Aaaa) => {
+                    (RecordData::Aaaa(address), ServiceProtocol::IPv6)
+                }
+                _ => continue,
+            };
+
+            handler(RecordEvent::RecordDiscovered(RecordInfo {
+                name: name.to_owned(),
+                record_class,
+                record_type,
+                data,
+                interface: 1,
+                protocol,
+            }));
         }
 
-        Ok(())
+        handler(RecordEvent::CacheExhausted);
+        handler(RecordEvent::AllDiscovered);
+
+        Ok(self.next_id())
     }
 
-    fn resolve(&self, service: ServiceInfo, listeners: ResolveListeners) {
-        let service = ServiceInfo {
-            address: Some(format!("192.168.1.1")),
+    fn start_resolve(
+        &self,
+        service: ServiceInfo,
+        address_protocol: ServiceProtocol,
+        flags: LookupFlags,
+        handler: ResolveEventHandler,
+    ) -> Result<usize, Error> {
+        let resolver_id = self.next_id();
+
+        let (host_name, port, txt, result_flags) = match self.find_service(&service)? {
+            Some(found) => found,
+            None => {
+                handler(Err(Error::AdapterFailure(format!(
+                    "Service {:?} is not found",
+                    service.name
+                ))));
+                return Ok(resolver_id);
+            }
+        };
+
+        let address = if flags.contains(LookupFlags::NO_ADDRESS) {
+            None
+        } else {
+            match self.find_address(&host_name, address_protocol) {
+                Some(address) => Some(address),
+                // Real resolver keeps looking for the address until it times out, fake
+                // one waits until it's stopped.
+                None => {
+                    self.pending_resolvers
+                        .lock()
+                        .unwrap()
+                        .insert(resolver_id, handler);
+                    return Ok(resolver_id);
+                }
+            }
+        };
+
+        let mut service = ServiceInfo {
+            address,
+            addresses: Vec::new(),
             domain: service.domain,
-            host_name: Some(format!("fake.local")),
+            host_name: Some(host_name),
             interface: service.interface,
             name: service.name,
-            port: 80,
+            port,
             protocol: service.protocol,
-            txt: Some(format!("\"model=Xserve\"")),
+            txt: if flags.contains(LookupFlags::NO_TXT) {
+                None
+            } else {
+                Some(txt)
+            },
             type_name: service.type_name,
+            flags: result_flags,
         };
 
-        if listeners.on_service_resolved.is_some() {
-            (*listeners.on_service_resolved.unwrap())(service);
+        service.addresses.extend(service.socket_addr());
+
+        handler(Ok(service));
+
+        Ok(resolver_id)
+    }
+
+    fn stop_resolve(&self, resolver_id: usize) {
+        self.pending_resolvers.lock().unwrap().remove(&resolver_id);
+    }
+
+    fn start_host_name_resolve(
+        &self,
+        host_name: &str,
+        address_protocol: ServiceProtocol,
+        _options: &DiscoveryOptions,
+        handler: HostResolveEventHandler,
+    ) -> Result<usize, Error> {
+        match self.find_address(host_name, address_protocol) {
+            Some(address) => handler(Ok(HostInfo {
+                host_name: host_name.to_owned(),
+                address,
+                interface: 1,
+                protocol: address_to_service_protocol(address),
+            })),
+            None => handler(Err(Error::AdapterFailure(format!(
+                "Host name {} is not found",
+                host_name
+            )))),
+        }
+
+        Ok(self.next_id())
+    }
+
+    fn stop_host_name_resolve(&self, _resolver_id: usize) {}
+
+    fn start_address_resolve(
+        &self,
+        address: IpAddr,
+        _options: &DiscoveryOptions,
+        handler: HostResolveEventHandler,
+    ) -> Result<usize, Error> {
+        let host_name = self
+            .hosts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|&(_, addresses)| addresses.contains(&address))
+            .map(|(host_name, _)| host_name.clone());
+
+        match host_name {
+            Some(host_name) => handler(Ok(HostInfo {
+                host_name,
+                address,
+                interface: 1,
+                protocol: address_to_service_protocol(address),
+            })),
+            None => handler(Err(Error::AdapterFailure(format!(
+                "Address {} is not found",
+                address
+            )))),
         }
+
+        Ok(self.next_id())
+    }
+
+    fn stop_address_resolve(&self, _resolver_id: usize) {}
+
+    fn stop_discovery(&self, browser_id: usize) {
+        // Dropping handler closes the channel discovery events are delivered through.
+        self.browsers.lock().unwrap().remove(&browser_id);
     }
 
-    fn stop_discovery(&self) {}
+    fn stop_all_discovery(&self) {
+        self.browsers.lock().unwrap().clear();
+    }
 }
 
 impl HostAdapter for FakeAdapter {
     fn get_name(&self) -> Result<String, Error> {
         FakeAdapter::print_warning();
-        return Ok("fake".to_owned());
+        Ok("fake".to_owned())
     }
 
     fn get_name_fqdn(&self) -> Result<String, Error> {
-        return Ok("fake.local".to_owned());
+        Ok("fake.local".to_owned())
     }
 
     fn set_name(&self, host_name: &str) -> Result<String, Error> {
@@ -80,18 +391,167 @@ impl HostAdapter for FakeAdapter {
         Ok(format!("{}-2", host_name))
     }
 
-    fn add_name_alias(&self, host_name: &str) -> Result<(), Error> {
-        warn!(
-            "Host name change request (-> {}) will be ignored.",
-            host_name
-        );
+    fn get_alternative_service_name(&self, service_name: &str) -> Result<String, Error> {
+        FakeAdapter::print_warning();
+        Ok(format!("{} #2", service_name))
+    }
+
+    fn add_name_alias(&self, alias: &NameAlias, handler: PublishEventHandler) -> Result<(), Error> {
+        FakeAdapter::print_warning();
+        debug!("Recording host name alias: {:?}.", alias);
+
+        self.aliases
+            .lock()
+            .unwrap()
+            .insert(alias.name.clone(), alias.clone());
+
+        // There is no network to collide with, so alias is established right away.
+        handler(PublishEvent::Established(alias.name.clone()));
+
         Ok(())
     }
 
-    fn announce_service(&self, service_name: &str, service_type: &str, port: u16) -> Result<(), Error> {
+    fn remove_name_alias(&self, name: &str) -> Result<(), Error> {
+        self.aliases
+            .lock()
+            .unwrap()
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| Error::Internal("Alias is not registered".to_owned()))
+    }
+
+    fn get_name_aliases(&self) -> Vec<(NameAlias, AliasState)> {
+        self.aliases
+            .lock()
+            .unwrap()
+            .values()
+            .map(|alias| (alias.clone(), AliasState::Established))
+            .collect()
+    }
+
+    fn register_service(
+        &self,
+        registration: &ServiceRegistration,
+        handler: ServiceEventHandler,
+    ) -> Result<usize, Error> {
         FakeAdapter::print_warning();
+        debug!("Recording service registration: {:?}.", registration);
+
+        let service_id = self.next_id();
+
+        self.services
+            .lock()
+            .unwrap()
+            .insert(service_id, registration.clone());
+
+        // There is no network to collide with, so service is established right away.
+        handler(ServiceEvent::Established(registration.name.clone()));
+        self.notify_browsers(
+            &registration.name,
+            &registration.service_type,
+            own_service_flags(),
+            DiscoveryEvent::ServiceDiscovered,
+        );
+
+        Ok(service_id)
+    }
+
+    fn update_service_txt(&self, service_id: usize, txt: &TxtRecord) -> Result<(), Error> {
+        match self.services.lock().unwrap().get_mut(&service_id) {
+            Some(registration) => {
+                registration.txt = txt.clone();
+                Ok(())
+            }
+            None => Err(Error::Internal("Service is not registered".to_owned())),
+        }
+    }
+
+    fn reset_service(&self, service_id: usize) -> Result<(), Error> {
+        debug!("Ignoring reset request for service {}.", service_id);
         Ok(())
     }
+
+    fn republish_service(&self, service_id: usize) -> Result<(), Error> {
+        debug!("Ignoring republish request for service {}.", service_id);
+        Ok(())
+    }
+
+    fn unregister_service(&self, service_id: usize) {
+        let registration = self.services.lock().unwrap().remove(&service_id);
+
+        if let Some(registration) = registration {
+            self.notify_browsers(
+                &registration.name,
+                &registration.service_type,
+                own_service_flags(),
+                DiscoveryEvent::ServiceRemoved,
+            );
+        }
+    }
+
+    fn get_service_name(&self, service_id: usize) -> Result<String, Error> {
+        self.services
+            .lock()
+            .unwrap()
+            .get(&service_id)
+            .map(|registration| registration.name.clone())
+            .ok_or_else(|| Error::Internal("Service is not registered".to_owned()))
+    }
+
+    fn publish_record(
+        &self,
+        registration: &RecordRegistration,
+        handler: PublishEventHandler,
+    ) -> Result<usize, Error> {
+        FakeAdapter::print_warning();
+        debug!("Recording record publishing: {:?}.", registration);
+
+        // Encode data anyway to report the same errors real adapter would.
+        registration.data.encode()?;
+
+        let record_id = self.next_id();
+        self.records
+            .lock()
+            .unwrap()
+            .insert(record_id, registration.name.clone());
+
+        handler(PublishEvent::Established(registration.name.clone()));
+
+        Ok(record_id)
+    }
+
+    fn publish_address(
+        &self,
+        registration: &AddressRegistration,
+        handler: PublishEventHandler,
+    ) -> Result<usize, Error> {
+        FakeAdapter::print_warning();
+        debug!("Recording address publishing: {:?}.", registration);
+
+        let record_id = self.next_id();
+        self.records
+            .lock()
+            .unwrap()
+            .insert(record_id, registration.name.clone());
+
+        // There is no network to collide with, so address is established right away.
+        handler(PublishEvent::Established(registration.name.clone()));
+
+        Ok(record_id)
+    }
+
+    fn unpublish_record(&self, record_id: usize) {
+        self.records.lock().unwrap().remove(&record_id);
+    }
+
+    fn get_record_name(&self, record_id: usize) -> Result<String, Error> {
+        self.records
+            .lock()
+            .unwrap()
+            .get(&record_id)
+            .cloned()
+            .ok_or_else(|| Error::Internal("Record is not published".to_owned()))
+    }
 }
 
 impl Drop for FakeAdapter {
@@ -103,15 +563,227 @@ impl Drop for FakeAdapter {
 impl Adapter for FakeAdapter {
     fn new() -> FakeAdapter {
         FakeAdapter::print_warning();
-        FakeAdapter
+        Default::default()
+    }
+}
+
+impl Default for FakeAdapter {
+    fn default() -> Self {
+        let mut hosts = HashMap::new();
+        hosts.insert(
+            "fake.local".to_owned(),
+            vec![
+                IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1)),
+                IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)),
+            ],
+        );
+
+        FakeAdapter {
+            hosts: Arc::new(Mutex::new(hosts)),
+            remote_services: Arc::new(Mutex::new(vec![RemoteService {
+                name: "fake".to_owned(),
+                service_type: "_http._tcp".to_owned(),
+                flags: LookupResultFlags::MULTICAST,
+            }])),
+            browsers: Arc::new(Mutex::new(HashMap::new())),
+            pending_resolvers: Arc::new(Mutex::new(HashMap::new())),
+            services: Arc::new(Mutex::new(HashMap::new())),
+            records: Arc::new(Mutex::new(HashMap::new())),
+            aliases: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+/// Flags services registered through the fake adapter are discovered with, all clones
+/// of the adapter are considered to be the same connection to the daemon.
+fn own_service_flags() -> LookupResultFlags {
+    LookupResultFlags::MULTICAST | LookupResultFlags::LOCAL | LookupResultFlags::OUR_OWN
+}
+
+fn address_to_service_protocol(address: IpAddr) -> ServiceProtocol {
+    if address.is_ipv6() {
+        ServiceProtocol::IPv6
+    } else {
+        ServiceProtocol::IPv4
     }
 }
 
+#[cfg(any(test, feature = "test-util"))]
 impl FakeAdapter {
-    fn print_warning() {
-        println!(
-            "WARNING: Your platform is not supported by real mDNS adapter, fake adapter is \
-             used!"
+    /// Adds address that `host_name` is resolved to and vice versa, `fake.local` is
+    /// resolved to `192.168.1.1` and `fe80::1` by default.
+    pub fn add_host(&self, host_name: &str, address: IpAddr) {
+        self.hosts
+            .lock()
+            .unwrap()
+            .entry(host_name.to_owned())
+            .or_default()
+            .push(address);
+    }
+
+    /// Forgets every address of `host_name`, services published by that host are not
+    /// resolved anymore.
+    pub fn remove_host(&self, host_name: &str) {
+        self.hosts.lock().unwrap().remove(host_name);
+    }
+
+    /// Advertises service of `service_type` as if it was published by another host,
+    /// running discovery sessions for that type get `ServiceDiscovered` event. `fake`
+    /// service of `_http._tcp` type is advertised by default.
+    pub fn add_service(&self, name: &str, service_type: &str) {
+        self.advertise(name, service_type, LookupResultFlags::MULTICAST);
+    }
+
+    /// Same as `add_service`, but service is advertised as if it was published by
+    /// another process of the local host, so it's reported with `LOCAL` flag.
+    pub fn add_local_service(&self, name: &str, service_type: &str) {
+        self.advertise(
+            name,
+            service_type,
+            LookupResultFlags::MULTICAST | LookupResultFlags::LOCAL,
         );
     }
+
+    /// Withdraws service added with `add_service`, running discovery sessions for that
+    /// type get `ServiceRemoved` event.
+    pub fn remove_service(&self, name: &str, service_type: &str) {
+        let position = self
+            .remote_services
+            .lock()
+            .unwrap()
+            .iter()
+            .position(|service| service.name == name && service.service_type == service_type);
+
+        if let Some(position) = position {
+            let service = self.remote_services.lock().unwrap().remove(position);
+
+            self.notify_browsers(
+                name,
+                service_type,
+                service.flags,
+                DiscoveryEvent::ServiceRemoved,
+            );
+        }
+    }
+
+    /// Returns services registered through this adapter (or its clones) that haven't
+    /// been unregistered yet, in the order they were registered.
+    pub fn registered_services(&self) -> Vec<ServiceRegistration> {
+        let services = self.services.lock().unwrap();
+        let mut service_ids: Vec<&usize> = services.keys().collect();
+        service_ids.sort();
+
+        service_ids
+            .into_iter()
+            .map(|service_id| services[service_id].clone())
+            .collect()
+    }
+}
+
+impl FakeAdapter {
+    /// Returns host name, port, TXT record and flags of the advertised or registered
+    /// service, services of other hosts are published by `fake.local`.
+    #[allow(clippy::type_complexity)]
+    fn find_service(
+        &self,
+        service: &ServiceInfo,
+    ) -> Result<Option<(String, u16, TxtRecord, LookupResultFlags)>, Error> {
+        let is_same = |name: &str, service_type: &str| {
+            service.name.as_deref() == Some(name)
+                && service.type_name.as_deref() == Some(service_type)
+        };
+
+        let remote_flags = self
+            .remote_services
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|remote| is_same(&remote.name, &remote.service_type))
+            .map(|remote| remote.flags);
+
+        if let Some(remote_flags) = remote_flags {
+            let mut txt = TxtRecord::new();
+            txt.insert("model", "Xserve")?;
+
+            return Ok(Some(("fake.local".to_owned(), 80, txt, remote_flags)));
+        }
+
+        Ok(self
+            .services
+            .lock()
+            .unwrap()
+            .values()
+            .find(|registration| is_same(&registration.name, &registration.service_type))
+            .map(|registration| {
+                (
+                    registration
+                        .host
+                        .clone()
+                        .unwrap_or_else(|| "fake.local".to_owned()),
+                    registration.port,
+                    registration.txt.clone(),
+                    own_service_flags(),
+                )
+            }))
+    }
+
+    /// Returns the first address of `host_name` of `address_protocol` family.
+    fn find_address(&self, host_name: &str, address_protocol: ServiceProtocol) -> Option<IpAddr> {
+        self.hosts
+            .lock()
+            .unwrap()
+            .get(host_name)
+            .and_then(|addresses| {
+                addresses
+                    .iter()
+                    .find(|address| match address_protocol {
+                        ServiceProtocol::IPv4 => address.is_ipv4(),
+                        ServiceProtocol::IPv6 => address.is_ipv6(),
+                        ServiceProtocol::Unspecified => true,
+                    })
+                    .cloned()
+            })
+    }
+
+    /// Adds service to `remote_services` and reports it to the running browsers.
+    #[cfg(any(test, feature = "test-util"))]
+    fn advertise(&self, name: &str, service_type: &str, flags: LookupResultFlags) {
+        self.remote_services.lock().unwrap().push(RemoteService {
+            name: name.to_owned(),
+            service_type: service_type.to_owned(),
+            flags,
+        });
+
+        self.notify_browsers(name, service_type, flags, DiscoveryEvent::ServiceDiscovered);
+    }
+
+    /// Reports `event` about the service to the running browsers of `service_type`.
+    fn notify_browsers(
+        &self,
+        name: &str,
+        service_type: &str,
+        flags: LookupResultFlags,
+        event: fn(DiscoveredService) -> DiscoveryEvent,
+    ) {
+        for browser in self.browsers.lock().unwrap().values() {
+            if browser.service_type == service_type {
+                (browser.handler)(event(browser.service(name, flags)));
+            }
+        }
+    }
+
+    fn next_id(&self) -> usize {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    fn print_warning() {
+        // Fake adapter can be used on purpose on the supported platforms.
+        if cfg!(not(target_os = "linux")) {
+            println!(
+                "WARNING: Your platform is not supported by real mDNS adapter, fake adapter \
+                 is used!"
+            );
+        }
+    }
 }
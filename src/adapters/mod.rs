@@ -5,8 +5,8 @@ mod avahi;
 #[cfg(target_os = "linux")]
 pub use adapters::avahi::AvahiAdapter as PlatformDependentAdapter;
 
-#[cfg(not(target_os = "linux"))]
-mod fake;
+#[cfg(any(test, feature = "test-util", not(target_os = "linux")))]
+pub mod fake;
 #[cfg(not(target_os = "linux"))]
 pub use adapters::fake::FakeAdapter as PlatformDependentAdapter;
 
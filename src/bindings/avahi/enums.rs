@@ -24,23 +24,27 @@ pub enum AvahiClientState {
 #[allow(dead_code, non_camel_case_types)]
 pub enum AvahiLookupFlags {
     AVAHI_LOOKUP_UNSPEC = 0,
+    /// Force lookup via wide area DNS.
+    AVAHI_LOOKUP_USE_WIDE_AREA = 1,
+    /// Force lookup via multicast DNS.
+    AVAHI_LOOKUP_USE_MULTICAST = 2,
     /// When doing service resolving, don't lookup TXT record.
-    AVAHI_LOOKUP_NO_TXT,
-
+    AVAHI_LOOKUP_NO_TXT = 4,
     /// When doing service resolving, don't lookup A/AAAA record.
-    AVAHI_LOOKUP_NO_ADDRESS,
+    AVAHI_LOOKUP_NO_ADDRESS = 8,
 }
 
+/// Lookup result flags are passed to the callbacks as a bit mask of these values.
 #[repr(C)]
 #[allow(dead_code, non_camel_case_types)]
 #[derive(Debug)]
 pub enum AvahiLookupResultFlags {
-    AVAHI_LOOKUP_RESULT_CACHED,
-    AVAHI_LOOKUP_RESULT_WIDE_AREA,
-    AVAHI_LOOKUP_RESULT_MULTICAST,
-    AVAHI_LOOKUP_RESULT_LOCAL,
-    AVAHI_LOOKUP_RESULT_OUR_OWN,
-    AVAHI_LOOKUP_RESULT_STATIC,
+    AVAHI_LOOKUP_RESULT_CACHED = 1,
+    AVAHI_LOOKUP_RESULT_WIDE_AREA = 2,
+    AVAHI_LOOKUP_RESULT_MULTICAST = 4,
+    AVAHI_LOOKUP_RESULT_LOCAL = 8,
+    AVAHI_LOOKUP_RESULT_OUR_OWN = 16,
+    AVAHI_LOOKUP_RESULT_STATIC = 32,
 }
 
 #[repr(C)]
@@ -87,6 +91,7 @@ pub enum AvahiResolverEvent {
 
 #[repr(C)]
 #[allow(dead_code, non_camel_case_types)]
+#[derive(Clone, Copy, Debug)]
 pub enum AvahiDomainBrowserType {
     /// Browse for a list of available browsing domains.
     AVAHI_DOMAIN_BROWSER_BROWSE,
@@ -109,6 +109,59 @@ extern "C" {
 
     pub fn avahi_alternative_host_name(host_name: *const c_char) -> *const c_char;
 
+    /// Find an alternative for the specified service name. If called with an original
+    /// service name, " #2" is appended. Afterwards the number is incremented on each
+    /// call (i.e. "foo" becomes "foo #2" becomes "foo #3" and so on).
+    /// `avahi_free` should always be called for the result!
+    ///
+    /// # Arguments
+    ///
+    /// * `service_name` - Service name to find alternative for.
+    pub fn avahi_alternative_service_name(service_name: *const c_char) -> *const c_char;
+
+    /// Create a new domain browser object that browses for domains of the specified type.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Active `AvahiClient` instance.
+    /// * `interface` - Interface to browse on, `AVAHI_IF_UNSPEC` for all.
+    /// * `protocol` - Protocol family specification `AvahiProtocol`.
+    /// * `domain` - Domain to look for domains in, `NULL` for the default domain.
+    /// * `btype` - The type of domain to browse for `AvahiDomainBrowserType`.
+    /// * `flags` - Flags for lookup functions `AvahiLookupFlags`.
+    /// * `callback` - `DomainBrowserCallback` callback to be called for every domain.
+    /// * `userdata` - Some arbitrary user data pointer that will be passed to the callback.
+    ///
+    /// # Return value
+    ///
+    /// A domain browser `AvahiDomainBrowser` object.
+    pub fn avahi_domain_browser_new(
+        client: *mut AvahiClient,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        domain: *const c_char,
+        btype: AvahiDomainBrowserType,
+        flags: c_int,
+        callback: DomainBrowserCallback,
+        userdata: *mut c_void,
+    ) -> *mut AvahiDomainBrowser;
+
+    /// Cleans up and frees an `AvahiDomainBrowser` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain_browser` - instance of `AvahiDomainBrowser`.
+    pub fn avahi_domain_browser_free(domain_browser: *mut AvahiDomainBrowser) -> c_int;
+
+    /// Get the parent client of an `AvahiDomainBrowser` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain_browser` - instance of `AvahiDomainBrowser`.
+    pub fn avahi_domain_browser_get_client(
+        domain_browser: *mut AvahiDomainBrowser,
+    ) -> *mut AvahiClient;
+
     /// Browse for domains on the local network.
     ///
     /// # Arguments
@@ -129,11 +182,11 @@ extern "C" {
     /// A domain browser `AvahiServiceBrowser` object.
     pub fn avahi_service_browser_new(
         client: *mut AvahiClient,
-        interface: AvahiIfIndex,
+        interface: c_int,
         protocol: AvahiProtocol,
         service_type: *const c_char,
         domain: *const c_char,
-        flags: AvahiLookupFlags,
+        flags: c_int,
         callback: ServiceBrowserCallback,
         userdata: *mut c_void,
     ) -> *mut AvahiServiceBrowser;
@@ -145,6 +198,58 @@ extern "C" {
     /// * `service_browser` - instance of `AvahiServiceBrowser`.
     pub fn avahi_service_browser_free(service_browser: *mut AvahiServiceBrowser) -> c_int;
 
+    /// Get the parent client of an `AvahiServiceBrowser` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_browser` - instance of `AvahiServiceBrowser`.
+    pub fn avahi_service_browser_get_client(
+        service_browser: *mut AvahiServiceBrowser,
+    ) -> *mut AvahiClient;
+
+    /// Browse for service types on the local network.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Active `AvahiClient` instance.
+    /// * `interface` - Interface to browse on, `AVAHI_IF_UNSPEC` for all.
+    /// * `protocol` - Protocol family specification `AvahiProtocol`.
+    /// * `domain` - Domain to browse in, `NULL` for the default domain.
+    /// * `flags` - Flags for lookup functions `AvahiLookupFlags`.
+    /// * `callback` - `ServiceTypeBrowserCallback` callback to be called for every type.
+    /// * `userdata` - Some arbitrary user data pointer that will be passed to the callback.
+    ///
+    /// # Return value
+    ///
+    /// A service type browser `AvahiServiceTypeBrowser` object.
+    pub fn avahi_service_type_browser_new(
+        client: *mut AvahiClient,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        domain: *const c_char,
+        flags: c_int,
+        callback: ServiceTypeBrowserCallback,
+        userdata: *mut c_void,
+    ) -> *mut AvahiServiceTypeBrowser;
+
+    /// Cleans up and frees an `AvahiServiceTypeBrowser` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_type_browser` - instance of `AvahiServiceTypeBrowser`.
+    pub fn avahi_service_type_browser_free(
+        service_type_browser: *mut AvahiServiceTypeBrowser,
+    ) -> c_int;
+
+    /// Get the parent client of an `AvahiServiceTypeBrowser` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_type_browser` - instance of `AvahiServiceTypeBrowser`.
+    pub fn avahi_service_type_browser_get_client(
+        service_type_browser: *mut AvahiServiceTypeBrowser,
+    ) -> *mut AvahiClient;
+
     /// Create a new service resolver object.
     ///
     /// Please make sure to pass all the service data you received via
@@ -187,13 +292,134 @@ extern "C" {
         service_type: *const c_char,
         domain: *const c_char,
         aprotocol: AvahiProtocol,
-        flags: AvahiLookupFlags,
+        flags: c_int,
         callback: ServiceResolverCallback,
         userdata: *mut c_void,
     ) -> *mut AvahiServiceResolver;
 
     pub fn avahi_service_resolver_free(resolver: *mut AvahiServiceResolver) -> c_int;
 
+    /// Get the parent client of an `AvahiServiceResolver` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - instance of `AvahiServiceResolver`.
+    pub fn avahi_service_resolver_get_client(
+        resolver: *mut AvahiServiceResolver,
+    ) -> *mut AvahiClient;
+
+    /// Create a new host name resolver object that resolves host name to an address.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Active `AvahiClient` instance.
+    /// * `interface` - Interface to resolve host name on, `AVAHI_IF_UNSPEC` for all.
+    /// * `protocol` - Protocol to use as transport for the queries.
+    /// * `name` - Host name to resolve, e.g. `printer.local`.
+    /// * `aprotocol` - Address family of the address we are looking for.
+    /// * `flags` - Flags for lookup functions `AvahiLookupFlags`.
+    /// * `callback` - `HostNameResolverCallback` callback to be called for every address.
+    /// * `userdata` - Some arbitrary user data pointer that will be passed to the callback.
+    ///
+    /// # Return value
+    ///
+    /// A host name resolver `AvahiHostNameResolver` object.
+    pub fn avahi_host_name_resolver_new(
+        client: *mut AvahiClient,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        name: *const c_char,
+        aprotocol: AvahiProtocol,
+        flags: c_int,
+        callback: HostNameResolverCallback,
+        userdata: *mut c_void,
+    ) -> *mut AvahiHostNameResolver;
+
+    pub fn avahi_host_name_resolver_free(resolver: *mut AvahiHostNameResolver) -> c_int;
+
+    /// Get the parent client of an `AvahiHostNameResolver` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - instance of `AvahiHostNameResolver`.
+    pub fn avahi_host_name_resolver_get_client(
+        resolver: *mut AvahiHostNameResolver,
+    ) -> *mut AvahiClient;
+
+    /// Create a new address resolver object that resolves address to a host name.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Active `AvahiClient` instance.
+    /// * `interface` - Interface to resolve address on, `AVAHI_IF_UNSPEC` for all.
+    /// * `protocol` - Protocol to use as transport for the queries.
+    /// * `address` - Address to resolve.
+    /// * `flags` - Flags for lookup functions `AvahiLookupFlags`.
+    /// * `callback` - `AddressResolverCallback` callback to be called for every host name.
+    /// * `userdata` - Some arbitrary user data pointer that will be passed to the callback.
+    ///
+    /// # Return value
+    ///
+    /// An address resolver `AvahiAddressResolver` object.
+    pub fn avahi_address_resolver_new(
+        client: *mut AvahiClient,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        address: *const AvahiAddress,
+        flags: c_int,
+        callback: AddressResolverCallback,
+        userdata: *mut c_void,
+    ) -> *mut AvahiAddressResolver;
+
+    pub fn avahi_address_resolver_free(resolver: *mut AvahiAddressResolver) -> c_int;
+
+    /// Get the parent client of an `AvahiAddressResolver` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolver` - instance of `AvahiAddressResolver`.
+    pub fn avahi_address_resolver_get_client(
+        resolver: *mut AvahiAddressResolver,
+    ) -> *mut AvahiClient;
+
+    /// Create a new record browser object that browses for arbitrary DNS records.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Active `AvahiClient` instance.
+    /// * `interface` - Interface to browse on, `AVAHI_IF_UNSPEC` for all.
+    /// * `protocol` - Protocol to use as transport for the queries.
+    /// * `name` - Name of the record to browse for, e.g. `printer.local`.
+    /// * `clazz` - DNS class of the record, e.g. `1` for `IN`.
+    /// * `record_type` - DNS type of the record, e.g. `13` for `HINFO`.
+    /// * `flags` - Flags for lookup functions `AvahiLookupFlags`.
+    /// * `callback` - `RecordBrowserCallback` callback to be called for every record.
+    /// * `userdata` - Some arbitrary user data pointer that will be passed to the callback.
+    ///
+    /// # Return value
+    ///
+    /// A record browser `AvahiRecordBrowser` object.
+    pub fn avahi_record_browser_new(
+        client: *mut AvahiClient,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        name: *const c_char,
+        clazz: u16,
+        record_type: u16,
+        flags: c_int,
+        callback: RecordBrowserCallback,
+        userdata: *mut c_void,
+    ) -> *mut AvahiRecordBrowser;
+
+    pub fn avahi_record_browser_free(browser: *mut AvahiRecordBrowser) -> c_int;
+
+    /// Get the parent client of an `AvahiRecordBrowser` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `browser` - instance of `AvahiRecordBrowser`.
+    pub fn avahi_record_browser_get_client(browser: *mut AvahiRecordBrowser) -> *mut AvahiClient;
+
     pub fn avahi_address_snprint(ret_s: *const c_char, length: size_t, a: *const AvahiAddress);
 
     /// Convert the string list object to a single character string, seperated by spaces
@@ -209,6 +435,55 @@ extern "C" {
     /// Single character string, seperated by spaces and enclosed in "".
     pub fn avahi_string_list_to_string(string_list: *mut AvahiStringList) -> *const c_char;
 
+    /// Returns the next item in the string list.
+    ///
+    /// # Arguments
+    ///
+    /// * `string_list` - string list instance.
+    ///
+    /// # Return value
+    ///
+    /// Next item in the string list or NULL if it's the last one.
+    pub fn avahi_string_list_get_next(string_list: *mut AvahiStringList) -> *mut AvahiStringList;
+
+    /// Returns the text of the current string list item, it's not NUL terminated.
+    ///
+    /// # Arguments
+    ///
+    /// * `string_list` - string list instance.
+    pub fn avahi_string_list_get_text(string_list: *mut AvahiStringList) -> *const u8;
+
+    /// Returns the size of the text of the current string list item.
+    ///
+    /// # Arguments
+    ///
+    /// * `string_list` - string list instance.
+    pub fn avahi_string_list_get_size(string_list: *mut AvahiStringList) -> size_t;
+
+    /// Prepend an arbitrary length byte string to the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `string_list` - string list instance, may be NULL to create a new list.
+    /// * `text` - byte string to prepend, it doesn't need to be NUL terminated.
+    /// * `size` - size of the byte string.
+    ///
+    /// # Return value
+    ///
+    /// The new list head.
+    pub fn avahi_string_list_add_arbitrary(
+        string_list: *mut AvahiStringList,
+        text: *const u8,
+        size: size_t,
+    ) -> *mut AvahiStringList;
+
+    /// Free a string list.
+    ///
+    /// # Arguments
+    ///
+    /// * `string_list` - string list instance.
+    pub fn avahi_string_list_free(string_list: *mut AvahiStringList);
+
     /// Free some memory.
     ///
     /// # Arguments
@@ -272,6 +547,21 @@ extern "C" {
 
     pub fn avahi_threaded_poll_quit(threaded_poll: *mut AvahiThreadedPoll) -> c_void;
 
+    /// Lock an event loop object, so that it's safe to access it and all its associated
+    /// objects from outside of the event loop helper thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `threaded_poll` - Main loop object returned from `avahi_threaded_poll_new`.
+    pub fn avahi_threaded_poll_lock(threaded_poll: *mut AvahiThreadedPoll) -> c_int;
+
+    /// Unlock an event loop object that has been locked with `avahi_threaded_poll_lock`.
+    ///
+    /// # Arguments
+    ///
+    /// * `threaded_poll` - Main loop object returned from `avahi_threaded_poll_new`.
+    pub fn avahi_threaded_poll_unlock(threaded_poll: *mut AvahiThreadedPoll) -> c_int;
+
     /// Free an event loop object.
     ///
     /// This will stop the associated event loop thread (if it is running).
@@ -291,15 +581,35 @@ extern "C" {
         group: *mut AvahiEntryGroup,
         interface: c_int,
         protocol: AvahiProtocol,
-        flags: AvahiPublishFlags,
+        flags: c_int,
         name: *const c_char,
-        record_class: AvahiRecordClass,
-        record_type: AvahiRecordType,
+        record_class: u16,
+        record_type: u16,
         ttl: u32,
         rdata: *const c_void,
         size: usize,
     ) -> c_int;
 
+    /// Add a host/address pair to the entry group, reverse (PTR) record is added as
+    /// well unless `AVAHI_PUBLISH_NO_REVERSE` flag is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The entry group to add address to.
+    /// * `interface` - Interface to publish address on, `AVAHI_IF_UNSPEC` for all.
+    /// * `protocol` - Protocol to publish address on, `AVAHI_PROTO_UNSPEC` for all.
+    /// * `flags` - Publish flags `AvahiPublishFlags`.
+    /// * `name` - Fully qualified host name to publish address for.
+    /// * `address` - Address to publish.
+    pub fn avahi_entry_group_add_address(
+        group: *mut AvahiEntryGroup,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        flags: c_int,
+        name: *const c_char,
+        address: *const AvahiAddress,
+    ) -> c_int;
+
     pub fn avahi_entry_group_add_service(
         group: *mut AvahiEntryGroup,
         interface: AvahiIfIndex,
@@ -313,7 +623,95 @@ extern "C" {
         text: *const c_void,
     ) -> c_int;
 
+    /// Add a service to an entry group, the TXT record is specified as a string list.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The entry group to add the service to.
+    /// * `interface` - Numeric network interface index or AVAHI_IF_UNSPEC (-1).
+    /// * `protocol` - Protocol family specification `AvahiProtocol`.
+    /// * `flags` - Combination of `AvahiPublishFlags`.
+    /// * `name` - The name of the service.
+    /// * `service_type` - The type of the service, i.e. `_http._tcp`.
+    /// * `domain` - The domain to register in, NULL for the default domain.
+    /// * `host` - The host this service is provided by, NULL for the local host.
+    /// * `port` - The IP port number of this service.
+    /// * `txt` - TXT record string list, may be NULL.
+    pub fn avahi_entry_group_add_service_strlst(
+        group: *mut AvahiEntryGroup,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        flags: c_int,
+        name: *const c_char,
+        service_type: *const c_char,
+        domain: *const c_char,
+        host: *const c_char,
+        port: u16,
+        txt: *mut AvahiStringList,
+    ) -> c_int;
+
+    /// Add a subtype for a service. The service must already be existent in the
+    /// entry group.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The entry group the service was added to.
+    /// * `interface`, `protocol`, `flags`, `name`, `service_type`, `domain` - The same
+    ///   values that were passed to `avahi_entry_group_add_service_strlst`.
+    /// * `subtype` - The subtype to register, i.e. `_printer._sub._http._tcp`.
+    pub fn avahi_entry_group_add_service_subtype(
+        group: *mut AvahiEntryGroup,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        flags: c_int,
+        name: *const c_char,
+        service_type: *const c_char,
+        domain: *const c_char,
+        subtype: *const c_char,
+    ) -> c_int;
+
     pub fn avahi_entry_group_commit(group: *mut AvahiEntryGroup) -> c_int;
 
+    /// Update a TXT record for an existing service. The service must already be
+    /// existent in the entry group, the rest of the arguments should match the ones
+    /// passed to `avahi_entry_group_add_service_strlst`.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The entry group the service was added to.
+    /// * `txt` - New TXT record string list, may be NULL.
+    pub fn avahi_entry_group_update_service_txt_strlst(
+        group: *mut AvahiEntryGroup,
+        interface: c_int,
+        protocol: AvahiProtocol,
+        flags: c_int,
+        name: *const c_char,
+        service_type: *const c_char,
+        domain: *const c_char,
+        txt: *mut AvahiStringList,
+    ) -> c_int;
+
+    /// Reset an entry group. This takes effect immediately, all the entries are
+    /// withdrawn and group can be filled and committed again.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The entry group to reset.
+    pub fn avahi_entry_group_reset(group: *mut AvahiEntryGroup) -> c_int;
+
+    /// Clean up and free an `AvahiEntryGroup` object, all its entries are withdrawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The entry group to free.
+    pub fn avahi_entry_group_free(group: *mut AvahiEntryGroup) -> c_int;
+
     pub fn avahi_entry_group_get_state(group: *mut AvahiEntryGroup) -> c_int;
+
+    /// Get the client of an entry group object.
+    ///
+    /// # Arguments
+    ///
+    /// * `group` - The entry group to get client for.
+    pub fn avahi_entry_group_get_client(group: *mut AvahiEntryGroup) -> *mut AvahiClient;
 }
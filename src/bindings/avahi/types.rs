@@ -1,5 +1,5 @@
 use super::enums::*;
-use libc::{c_char, c_int, c_void};
+use libc::{c_char, c_int, c_void, size_t};
 
 /// A main loop object.
 /// Main loops of this type aren't very flexible since they only support a single wakeup type.
@@ -45,8 +45,36 @@ pub struct AvahiAddressResolver;
 #[repr(C)]
 pub struct AvahiRecordBrowser;
 
+/// IPv4 address in network byte order.
 #[repr(C)]
-pub struct AvahiAddress;
+#[derive(Clone, Copy)]
+pub struct AvahiIPv4Address {
+    pub address: u32,
+}
+
+/// IPv6 address in network byte order.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct AvahiIPv6Address {
+    pub address: [u8; 16],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union AvahiAddressData {
+    pub ipv6: AvahiIPv6Address,
+    pub ipv4: AvahiIPv4Address,
+    pub data: [u8; 1],
+}
+
+/// Protocol (address family) independent address structure.
+#[repr(C)]
+pub struct AvahiAddress {
+    /// Address family, one of `AvahiProtocol` values. It's kept as `c_int` since
+    /// it's read from memory filled by Avahi.
+    pub proto: c_int,
+    pub data: AvahiAddressData,
+}
 
 #[repr(C)]
 pub struct AvahiStringList;
@@ -78,6 +106,16 @@ pub struct AvahiClient {
 
 pub type ClientCallback = extern "C" fn(*const AvahiClient, AvahiClientState, *const c_void);
 
+pub type DomainBrowserCallback = extern "C" fn(
+    *const AvahiDomainBrowser,
+    c_int,
+    AvahiProtocol,
+    AvahiBrowserEvent,
+    *const c_char,
+    c_int,
+    *const c_void,
+);
+
 pub type ServiceBrowserCallback = extern "C" fn(
     *const AvahiServiceBrowser,
     c_int,
@@ -86,7 +124,18 @@ pub type ServiceBrowserCallback = extern "C" fn(
     *const c_char,
     *const c_char,
     *const c_char,
-    AvahiLookupResultFlags,
+    c_int,
+    *const c_void,
+);
+
+pub type ServiceTypeBrowserCallback = extern "C" fn(
+    *const AvahiServiceTypeBrowser,
+    c_int,
+    AvahiProtocol,
+    AvahiBrowserEvent,
+    *const c_char,
+    *const c_char,
+    c_int,
     *const c_void,
 );
 
@@ -102,11 +151,51 @@ pub type ServiceResolverCallback = extern "C" fn(
     *const AvahiAddress,
     u16,
     *mut AvahiStringList,
-    AvahiLookupResultFlags,
+    c_int,
+    *const c_void,
+);
+
+pub type HostNameResolverCallback = extern "C" fn(
+    *const AvahiHostNameResolver,
+    c_int,
+    AvahiProtocol,
+    AvahiResolverEvent,
+    *const c_char,
+    *const AvahiAddress,
+    c_int,
+    *const c_void,
+);
+
+pub type AddressResolverCallback = extern "C" fn(
+    *const AvahiAddressResolver,
+    c_int,
+    AvahiProtocol,
+    AvahiResolverEvent,
+    *const AvahiAddress,
+    *const c_char,
+    c_int,
+    *const c_void,
+);
+
+pub type RecordBrowserCallback = extern "C" fn(
+    *const AvahiRecordBrowser,
+    c_int,
+    AvahiProtocol,
+    AvahiBrowserEvent,
+    *const c_char,
+    u16,
+    u16,
+    *const c_void,
+    size_t,
+    c_int,
     *const c_void,
 );
 
 pub type AvahiEntryGroupCallback =
     extern "C" fn(*const AvahiEntryGroup, AvahiEntryGroupState, *const c_void);
 
+#[allow(dead_code)]
 pub static AVAHI_ADDRESS_STR_MAX: usize = 4 * 8 + 7 + 1; // 1 is for NUL
+
+/// Error code Avahi uses to report local name collisions.
+pub static AVAHI_ERR_COLLISION: c_int = -8;
@@ -0,0 +1,319 @@
+use std::convert::TryFrom;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::{Future, Stream, StreamExt};
+
+use adapters::adapter::DiscoveryAdapter;
+use adapters::errors::Error;
+use discovery::discovery_manager::{
+    DiscoveryEvent, DiscoveryManager, HostInfo, ServiceInfo, ServiceProtocol,
+};
+use discovery::discovery_options::DiscoveryOptions;
+use discovery::lookup_flags::LookupFlags;
+use discovery::service::{DiscoveredService, ResolvedService};
+
+/// Stream of the discovery events, discovery is stopped as soon as the stream is
+/// dropped. Stream ends once discovery has been stopped.
+///
+/// Stream shares the adapter with the manager it has been created by, so it's `Send`,
+/// can outlive the manager and be passed to `tokio::spawn`.
+#[must_use = "streams do nothing unless polled"]
+pub struct DiscoveryStream {
+    adapter: Arc<dyn DiscoveryAdapter>,
+    browser_id: usize,
+    receiver: mpsc::UnboundedReceiver<DiscoveryEvent>,
+}
+
+impl DiscoveryStream {
+    /// Returns identifier that distinguishes this stream from the other discovery
+    /// sessions running on the same manager.
+    pub fn id(&self) -> usize {
+        self.browser_id
+    }
+}
+
+impl Stream for DiscoveryStream {
+    type Item = DiscoveryEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for DiscoveryStream {
+    fn drop(&mut self) {
+        self.adapter.stop_discovery(self.browser_id);
+    }
+}
+
+/// Future that completes once service is resolved, resolution is cancelled as soon
+/// as the future is dropped. Just like `DiscoveryStream`, it's `Send`.
+#[must_use = "futures do nothing unless polled"]
+pub struct ResolveFuture {
+    adapter: Arc<dyn DiscoveryAdapter>,
+    resolver_id: usize,
+    receiver: mpsc::UnboundedReceiver<Result<ServiceInfo, Error>>,
+}
+
+impl Future for ResolveFuture {
+    type Output = Result<ResolvedService, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.receiver.poll_next_unpin(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(result.and_then(ResolvedService::try_from)),
+            Poll::Ready(None) => Poll::Ready(Err(Error::Internal(
+                "Service resolver has been stopped".to_owned(),
+            ))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for ResolveFuture {
+    fn drop(&mut self) {
+        self.adapter.stop_resolve(self.resolver_id);
+    }
+}
+
+/// Future that completes once host name or address is resolved, resolution is
+/// cancelled as soon as the future is dropped.
+#[must_use = "futures do nothing unless polled"]
+pub struct HostResolveFuture {
+    adapter: Arc<dyn DiscoveryAdapter>,
+    resolver_id: usize,
+    /// Stops host name or address resolver, depending on what is resolved.
+    stop: fn(&dyn DiscoveryAdapter, usize),
+    receiver: mpsc::UnboundedReceiver<Result<HostInfo, Error>>,
+}
+
+impl Future for HostResolveFuture {
+    type Output = Result<HostInfo, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.receiver.poll_next_unpin(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(result),
+            Poll::Ready(None) => Poll::Ready(Err(Error::Internal(
+                "Host resolver has been stopped".to_owned(),
+            ))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for HostResolveFuture {
+    fn drop(&mut self) {
+        (self.stop)(&*self.adapter, self.resolver_id);
+    }
+}
+
+impl DiscoveryManager {
+    /// Same as `start_discovery`, but discovery events are delivered through `Stream`.
+    pub fn discover_services_async(&self, service_type: &str) -> Result<DiscoveryStream, Error> {
+        self.discover_services_async_with_options(service_type, DiscoveryOptions::new())
+    }
+
+    /// Same as `start_discovery_with_options`, but discovery events are delivered
+    /// through `Stream`.
+    pub fn discover_services_async_with_options(
+        &self,
+        service_type: &str,
+        options: DiscoveryOptions,
+    ) -> Result<DiscoveryStream, Error> {
+        let (sender, receiver) = mpsc::unbounded();
+        let filter = options.clone();
+
+        let browser_id = self.adapter.start_discovery(
+            service_type,
+            &options,
+            Box::new(move |event| {
+                if filter.skips(&event) {
+                    return;
+                }
+
+                // Receiver is gone only if stream has been dropped.
+                let _ = sender.unbounded_send(event);
+            }),
+        )?;
+
+        Ok(DiscoveryStream {
+            adapter: self.adapter.clone(),
+            browser_id,
+            receiver,
+        })
+    }
+
+    /// Same as `resolve_service`, but resolved service is delivered through `Future`.
+    pub fn resolve_service_async(
+        &self,
+        service: &DiscoveredService,
+    ) -> Result<ResolveFuture, Error> {
+        let (sender, receiver) = mpsc::unbounded();
+
+        let resolver_id = self.adapter.start_resolve(
+            ServiceInfo::from(service.clone()),
+            ServiceProtocol::Unspecified,
+            LookupFlags::empty(),
+            Box::new(move |result| {
+                // Receiver is gone only if future has been dropped.
+                let _ = sender.unbounded_send(result);
+            }),
+        )?;
+
+        Ok(ResolveFuture {
+            adapter: self.adapter.clone(),
+            resolver_id,
+            receiver,
+        })
+    }
+
+    /// Same as `resolve_host_name_with_options`, but resolved host is delivered through
+    /// `Future`. Use `futures` or runtime timers to limit time it takes.
+    pub fn resolve_host_name_async(
+        &self,
+        host_name: &str,
+        protocol: ServiceProtocol,
+        options: DiscoveryOptions,
+    ) -> Result<HostResolveFuture, Error> {
+        let (sender, receiver) = mpsc::unbounded();
+
+        let resolver_id = self.adapter.start_host_name_resolve(
+            host_name,
+            protocol,
+            &options,
+            Box::new(move |result| {
+                // Receiver is gone only if future has been dropped.
+                let _ = sender.unbounded_send(result);
+            }),
+        )?;
+
+        Ok(HostResolveFuture {
+            adapter: self.adapter.clone(),
+            resolver_id,
+            stop: |adapter, resolver_id| adapter.stop_host_name_resolve(resolver_id),
+            receiver,
+        })
+    }
+
+    /// Same as `resolve_address_with_options`, but resolved host is delivered through
+    /// `Future`.
+    pub fn resolve_address_async(
+        &self,
+        address: IpAddr,
+        options: DiscoveryOptions,
+    ) -> Result<HostResolveFuture, Error> {
+        let (sender, receiver) = mpsc::unbounded();
+
+        let resolver_id = self.adapter.start_address_resolve(
+            address,
+            &options,
+            Box::new(move |result| {
+                // Receiver is gone only if future has been dropped.
+                let _ = sender.unbounded_send(result);
+            }),
+        )?;
+
+        Ok(HostResolveFuture {
+            adapter: self.adapter.clone(),
+            resolver_id,
+            stop: |adapter, resolver_id| adapter.stop_address_resolve(resolver_id),
+            receiver,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::thread;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use adapters::fake::FakeAdapter;
+
+    #[test]
+    fn streams_discovery_events_until_stopped() {
+        let fake = FakeAdapter::default();
+        let manager = DiscoveryManager::with_fake_adapter(fake.clone());
+        let mut stream = manager.discover_services_async("_http._tcp").unwrap();
+
+        let events: Vec<_> = block_on(stream.by_ref().take(3).collect());
+        match events[0] {
+            DiscoveryEvent::ServiceDiscovered(ref service) => {
+                assert_eq!(service.name, "fake")
+            }
+            ref event => panic!("Unexpected event: {:?}", event),
+        }
+        assert!(matches!(events[1], DiscoveryEvent::CacheExhausted));
+        assert!(matches!(events[2], DiscoveryEvent::AllDiscovered));
+
+        fake.remove_service("fake", "_http._tcp");
+        match block_on(stream.next()) {
+            Some(DiscoveryEvent::ServiceRemoved(service)) => {
+                assert_eq!(service.name, "fake")
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
+
+        manager.stop_service_discovery();
+        assert!(block_on(stream.next()).is_none());
+    }
+
+    #[test]
+    fn resolves_discovered_service() {
+        let manager = DiscoveryManager::with_fake_adapter(FakeAdapter::default());
+        let service = match manager.start_discovery("_http._tcp").unwrap().recv() {
+            Some(DiscoveryEvent::ServiceDiscovered(service)) => service,
+            event => panic!("Unexpected event: {:?}", event),
+        };
+
+        let service = block_on(manager.resolve_service_async(&service).unwrap()).unwrap();
+
+        assert_eq!(service.host_name, "fake.local");
+        assert_eq!(service.port, 80);
+        assert!(!service.addresses.is_empty());
+    }
+
+    #[test]
+    fn streams_outlive_manager_and_move_between_threads() {
+        let manager = DiscoveryManager::with_fake_adapter(FakeAdapter::default());
+        let stream = manager.discover_services_async("_http._tcp").unwrap();
+        drop(manager);
+
+        let events = thread::spawn(move || block_on(stream.take(3).collect::<Vec<_>>()))
+            .join()
+            .unwrap();
+
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[2], DiscoveryEvent::AllDiscovered));
+    }
+
+    #[test]
+    fn resolves_host_name_and_address() {
+        let manager = DiscoveryManager::with_fake_adapter(FakeAdapter::default());
+        let address = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1));
+
+        let host = manager
+            .resolve_host_name_async("fake.local", ServiceProtocol::IPv4, DiscoveryOptions::new())
+            .unwrap();
+        assert_eq!(block_on(host).unwrap().address, address);
+
+        let host = manager
+            .resolve_address_async(address, DiscoveryOptions::new())
+            .unwrap();
+        assert_eq!(block_on(host).unwrap().host_name, "fake.local");
+
+        let host = manager
+            .resolve_host_name_async(
+                "unknown.local",
+                ServiceProtocol::IPv4,
+                DiscoveryOptions::new(),
+            )
+            .unwrap();
+        assert!(block_on(host).is_err());
+    }
+}
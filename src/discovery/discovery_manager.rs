@@ -1,18 +1,40 @@
+use std::convert::TryFrom;
+use std::net::{IpAddr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
 use adapters::adapter::Adapter;
 use adapters::adapter::DiscoveryAdapter;
 use adapters::errors::Error;
+#[cfg(any(test, feature = "test-util"))]
+use adapters::fake::FakeAdapter;
 use adapters::PlatformDependentAdapter;
+use discovery::discovery_options::DiscoveryOptions;
+use discovery::dns_record::{RecordData, RecordType};
+use discovery::lookup_flags::{LookupFlags, LookupResultFlags};
+use discovery::service::{DiscoveredService, ResolvedService};
+use discovery::txt_record::TxtRecord;
+
+/// How long `resolve_service_aggregated` waits for the remaining resolvers once the
+/// service has been resolved by one of them.
+pub const AGGREGATION_GRACE_PERIOD: Duration = Duration::from_millis(500);
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ServiceProtocol {
     IPv4 = 0,
     IPv6 = 1,
     Unspecified = -1,
 }
 
+/// Service as it's reported by adapters, fields that haven't been looked up yet are
+/// empty. It converts into `DiscoveredService` or `ResolvedService` that managers
+/// report to the caller.
 #[derive(Debug)]
 pub struct ServiceInfo {
-    pub address: Option<String>,
+    pub address: Option<IpAddr>,
+    /// Socket addresses of the resolved service, contains every address found by
+    /// `resolve_service_aggregated` and at most one address otherwise.
+    pub addresses: Vec<SocketAddr>,
     pub domain: Option<String>,
     pub host_name: Option<String>,
     pub interface: i32,
@@ -20,20 +42,230 @@ pub struct ServiceInfo {
     pub port: u16,
     pub protocol: ServiceProtocol,
     pub type_name: Option<String>,
-    pub txt: Option<String>,
+    pub txt: Option<TxtRecord>,
+    /// Describes where service has been found, e.g. whether it's published locally.
+    pub flags: LookupResultFlags,
 }
 
+impl ServiceInfo {
+    /// Returns socket address of the resolved service. Interface index is used as
+    /// scope id for IPv6 link-local addresses, so that they can be connected to.
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        self.address.map(|address| match address {
+            IpAddr::V4(address) => SocketAddr::V4(SocketAddrV4::new(address, self.port)),
+            IpAddr::V6(address) => {
+                let is_link_local = address.segments()[0] & 0xffc0 == 0xfe80;
+                let scope_id = if is_link_local && self.interface > 0 {
+                    self.interface as u32
+                } else {
+                    0
+                };
+
+                SocketAddr::V6(SocketAddrV6::new(address, self.port, 0, scope_id))
+            }
+        })
+    }
+
+    /// Returns TXT record in the legacy `"key=value" "key2=value2"` string form.
+    pub fn txt_string(&self) -> Option<String> {
+        self.txt.as_ref().map(|txt| txt.to_string())
+    }
+
+    /// Returns `true` if service is published by the local host.
+    pub fn is_local(&self) -> bool {
+        self.flags.contains(LookupResultFlags::LOCAL)
+    }
+
+    /// Returns `true` if service is published through the same connection to the mDNS
+    /// daemon it has been found with. It's the case for services registered with the
+    /// `HostManager` that discovery manager has been obtained from with
+    /// `HostManager::discovery_manager`, managers created separately use separate
+    /// connections.
+    pub fn is_our_own(&self) -> bool {
+        self.flags.contains(LookupResultFlags::OUR_OWN)
+    }
+}
+
+/// Host name along with one of its addresses.
+#[derive(Clone, Debug)]
+pub struct HostInfo {
+    pub host_name: String,
+    pub address: IpAddr,
+    /// Index of the network interface address has been found on.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+}
+
+#[derive(Debug)]
+pub enum DiscoveryEvent {
+    ServiceDiscovered(DiscoveredService),
+    /// Previously discovered service has left the network.
+    ServiceRemoved(DiscoveredService),
+    /// No more services are expected to be found in the cache, services that are
+    /// discovered afterwards come from the network.
+    CacheExhausted,
+    AllDiscovered,
+    /// Service browser has failed, no more events will be delivered.
+    Failed(Error),
+}
+
+/// Service type advertised on the network.
+#[derive(Clone, Debug)]
+pub struct ServiceTypeInfo {
+    pub service_type: String,
+    pub domain: String,
+    /// Index of the network interface service type has been found on.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+}
+
+#[derive(Debug)]
+pub enum ServiceTypeEvent {
+    ServiceTypeDiscovered(ServiceTypeInfo),
+    /// There are no services of the previously discovered type anymore.
+    ServiceTypeRemoved(ServiceTypeInfo),
+    /// No more service types are expected to be found in the cache.
+    CacheExhausted,
+    AllDiscovered,
+    /// Service type browser has failed, no more events will be delivered.
+    Failed(Error),
+}
+
+/// Kind of domains to look for with `DiscoveryManager::discover_domains`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainType {
+    /// Domains recommended for browsing.
+    Browse,
+    /// Default domain for browsing.
+    BrowseDefault,
+    /// Domains recommended for registering services.
+    Register,
+    /// Default domain for registering services.
+    RegisterDefault,
+    /// Legacy browsing domains, see RFC 6763, section 11.
+    BrowseLegacy,
+}
+
+/// Domain advertised on the network.
+#[derive(Clone, Debug)]
+pub struct DomainInfo {
+    pub domain: String,
+    /// Index of the network interface domain has been found on.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+}
+
+#[derive(Debug)]
+pub enum DomainEvent {
+    DomainDiscovered(DomainInfo),
+    /// Previously discovered domain is not advertised anymore.
+    DomainRemoved(DomainInfo),
+    /// No more domains are expected to be found in the cache.
+    CacheExhausted,
+    AllDiscovered,
+    /// Domain browser has failed, no more events will be delivered.
+    Failed(Error),
+}
+
+/// DNS resource record found on the network.
+#[derive(Clone, Debug)]
+pub struct RecordInfo {
+    pub name: String,
+    pub record_class: u16,
+    pub record_type: RecordType,
+    pub data: RecordData,
+    /// Index of the network interface record has been found on.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+}
+
+#[derive(Debug)]
+pub enum RecordEvent {
+    RecordDiscovered(RecordInfo),
+    /// Previously discovered record has expired or has been withdrawn.
+    RecordRemoved(RecordInfo),
+    /// No more records are expected to be found in the cache.
+    CacheExhausted,
+    AllDiscovered,
+    /// Record browser has failed, no more events will be delivered.
+    Failed(Error),
+}
+
+#[derive(Default)]
 pub struct DiscoveryListeners<'a> {
-    pub on_service_discovered: Option<&'a dyn Fn(ServiceInfo)>,
+    pub on_service_discovered: Option<&'a dyn Fn(DiscoveredService)>,
+    /// Called when previously discovered service has left the network.
+    pub on_service_removed: Option<&'a dyn Fn(DiscoveredService)>,
+    /// Called when no more services are expected to be found in the cache, services
+    /// that are discovered afterwards come from the network.
+    pub on_cache_exhausted: Option<&'a dyn Fn()>,
     pub on_all_discovered: Option<&'a dyn Fn()>,
+    /// Called when service browser fails, discovery is stopped afterwards.
+    pub on_discovery_failed: Option<&'a dyn Fn(Error)>,
 }
 
+#[derive(Default)]
 pub struct ResolveListeners<'a> {
-    pub on_service_resolved: Option<&'a dyn Fn(ServiceInfo)>,
+    pub on_service_resolved: Option<&'a dyn Fn(ResolvedService)>,
+    /// Called when service couldn't be resolved or resolution has timed out.
+    pub on_resolve_failed: Option<&'a dyn Fn(Error)>,
 }
 
 pub struct DiscoveryManager {
-    adapter: Box<dyn DiscoveryAdapter>,
+    pub(crate) adapter: Arc<dyn DiscoveryAdapter>,
+}
+
+/// Handle to the running discovery of services (or service types, depending on `E`).
+/// Discovery runs in the background and its events are queued until they are
+/// received, discovery is stopped as soon as the session is dropped.
+#[must_use = "discovery is stopped as soon as the session is dropped"]
+pub struct DiscoverySession<E = DiscoveryEvent> {
+    adapter: Arc<dyn DiscoveryAdapter>,
+    browser_id: usize,
+    receiver: mpsc::Receiver<E>,
+}
+
+impl<E> DiscoverySession<E> {
+    /// Returns identifier that distinguishes this session from the other sessions
+    /// running on the same manager.
+    pub fn id(&self) -> usize {
+        self.browser_id
+    }
+
+    /// Blocks until the next event is available, returns `None` once discovery has
+    /// been stopped.
+    pub fn recv(&self) -> Option<E> {
+        self.receiver.recv().ok()
+    }
+
+    /// Returns the next event if it's already available.
+    pub fn try_recv(&self) -> Option<E> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Blocks until the next event is available or `timeout` elapses.
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<E> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// Returns blocking iterator over events that ends once discovery has been stopped.
+    pub fn iter(&self) -> mpsc::Iter<'_, E> {
+        self.receiver.iter()
+    }
+
+    /// Returns iterator over the events that are already available.
+    pub fn try_iter(&self) -> mpsc::TryIter<'_, E> {
+        self.receiver.try_iter()
+    }
+
+    /// Stops discovery, it's the same as dropping the session.
+    pub fn stop(self) {}
+}
+
+impl<E> Drop for DiscoverySession<E> {
+    fn drop(&mut self) {
+        self.adapter.stop_discovery(self.browser_id);
+    }
 }
 
 impl DiscoveryManager {
@@ -41,27 +273,924 @@ impl DiscoveryManager {
         Default::default()
     }
 
+    /// Creates manager that uses `FakeAdapter` instead of the platform adapter, so that
+    /// code can be exercised without mDNS daemon and with known hosts.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn with_fake_adapter(adapter: FakeAdapter) -> Self {
+        DiscoveryManager {
+            adapter: Arc::new(adapter),
+        }
+    }
+
+    /// Starts discovery of the services of the specified type in the background,
+    /// discovery events can be received through the returned session. Any number of
+    /// sessions can run simultaneously, each one receives only its own events.
+    pub fn start_discovery(&self, service_type: &str) -> Result<DiscoverySession, Error> {
+        self.start_discovery_in_domain(service_type, None)
+    }
+
+    /// Same as `start_discovery`, but services are looked for in `domain` (e.g. one
+    /// found with `discover_domains`), the default domain is used if it's `None`.
+    pub fn start_discovery_in_domain(
+        &self,
+        service_type: &str,
+        domain: Option<&str>,
+    ) -> Result<DiscoverySession, Error> {
+        self.start_discovery_with_options(
+            service_type,
+            options_with_flags(domain, LookupFlags::empty()),
+        )
+    }
+
+    /// Same as `start_discovery`, but services are looked for only on the interface,
+    /// over the protocol and in the domain specified in `options`.
+    pub fn start_discovery_with_options(
+        &self,
+        service_type: &str,
+        options: DiscoveryOptions,
+    ) -> Result<DiscoverySession, Error> {
+        let (sender, receiver) = mpsc::channel();
+        let filter = options.clone();
+
+        let browser_id = self.adapter.start_discovery(
+            service_type,
+            &options,
+            Box::new(move |event| {
+                if filter.skips(&event) {
+                    return;
+                }
+
+                // Receiver is gone only if session has been dropped.
+                let _ = sender.send(event);
+            }),
+        )?;
+
+        Ok(DiscoverySession {
+            adapter: self.adapter.clone(),
+            browser_id,
+            receiver,
+        })
+    }
+
+    /// Starts discovery of all service types advertised in `domain` (the default domain
+    /// if `None`) in the background, discovery events can be received through the
+    /// returned session.
+    pub fn discover_service_types(
+        &self,
+        domain: Option<&str>,
+    ) -> Result<DiscoverySession<ServiceTypeEvent>, Error> {
+        self.discover_service_types_with_flags(domain, LookupFlags::empty())
+    }
+
+    /// Same as `discover_service_types`, but service types are looked up using `flags`.
+    pub fn discover_service_types_with_flags(
+        &self,
+        domain: Option<&str>,
+        flags: LookupFlags,
+    ) -> Result<DiscoverySession<ServiceTypeEvent>, Error> {
+        self.discover_service_types_with_options(options_with_flags(domain, flags))
+    }
+
+    /// Same as `discover_service_types`, but service types are looked up on the
+    /// interface, over the protocol and in the domain specified in `options`.
+    /// Skip options are ignored.
+    pub fn discover_service_types_with_options(
+        &self,
+        options: DiscoveryOptions,
+    ) -> Result<DiscoverySession<ServiceTypeEvent>, Error> {
+        let (sender, receiver) = mpsc::channel();
+
+        let browser_id = self.adapter.start_service_type_discovery(
+            &options,
+            Box::new(move |event| {
+                // Receiver is gone only if session has been dropped.
+                let _ = sender.send(event);
+            }),
+        )?;
+
+        Ok(DiscoverySession {
+            adapter: self.adapter.clone(),
+            browser_id,
+            receiver,
+        })
+    }
+
+    /// Starts discovery of the domains of the specified type in `domain` (the default
+    /// domain if `None`) in the background, discovery events can be received through
+    /// the returned session.
+    pub fn discover_domains(
+        &self,
+        domain: Option<&str>,
+        domain_type: DomainType,
+    ) -> Result<DiscoverySession<DomainEvent>, Error> {
+        self.discover_domains_with_flags(domain, domain_type, LookupFlags::empty())
+    }
+
+    /// Same as `discover_domains`, but domains are looked up using `flags`.
+    pub fn discover_domains_with_flags(
+        &self,
+        domain: Option<&str>,
+        domain_type: DomainType,
+        flags: LookupFlags,
+    ) -> Result<DiscoverySession<DomainEvent>, Error> {
+        self.discover_domains_with_options(domain_type, options_with_flags(domain, flags))
+    }
+
+    /// Same as `discover_domains`, but domains are looked up on the interface, over
+    /// the protocol and in the domain specified in `options`. Skip options are
+    /// ignored.
+    pub fn discover_domains_with_options(
+        &self,
+        domain_type: DomainType,
+        options: DiscoveryOptions,
+    ) -> Result<DiscoverySession<DomainEvent>, Error> {
+        let (sender, receiver) = mpsc::channel();
+
+        let browser_id = self.adapter.start_domain_discovery(
+            domain_type,
+            &options,
+            Box::new(move |event| {
+                // Receiver is gone only if session has been dropped.
+                let _ = sender.send(event);
+            }),
+        )?;
+
+        Ok(DiscoverySession {
+            adapter: self.adapter.clone(),
+            browser_id,
+            receiver,
+        })
+    }
+
+    /// Starts browsing for DNS records of `record_type` and `record_class` (usually
+    /// `CLASS_IN`) with the specified name in the background, record events can be
+    /// received through the returned session.
+    pub fn discover_records(
+        &self,
+        name: &str,
+        record_class: u16,
+        record_type: RecordType,
+    ) -> Result<DiscoverySession<RecordEvent>, Error> {
+        self.discover_records_with_flags(name, record_class, record_type, LookupFlags::empty())
+    }
+
+    /// Same as `discover_records`, but records are looked up using `flags`.
+    pub fn discover_records_with_flags(
+        &self,
+        name: &str,
+        record_class: u16,
+        record_type: RecordType,
+        flags: LookupFlags,
+    ) -> Result<DiscoverySession<RecordEvent>, Error> {
+        self.discover_records_with_options(
+            name,
+            record_class,
+            record_type,
+            DiscoveryOptions::new().flags(flags),
+        )
+    }
+
+    /// Same as `discover_records`, but records are looked up on the interface and over
+    /// the protocol specified in `options`. `domain` and skip options are
+    /// ignored, since record name is fully qualified.
+    pub fn discover_records_with_options(
+        &self,
+        name: &str,
+        record_class: u16,
+        record_type: RecordType,
+        options: DiscoveryOptions,
+    ) -> Result<DiscoverySession<RecordEvent>, Error> {
+        let (sender, receiver) = mpsc::channel();
+
+        let browser_id = self.adapter.start_record_discovery(
+            name,
+            record_class,
+            record_type,
+            &options,
+            Box::new(move |event| {
+                // Receiver is gone only if session has been dropped.
+                let _ = sender.send(event);
+            }),
+        )?;
+
+        Ok(DiscoverySession {
+            adapter: self.adapter.clone(),
+            browser_id,
+            receiver,
+        })
+    }
+
+    /// Discovers services of the specified type and dispatches events to `listeners`.
+    /// Blocks the calling thread until discovery fails or `stop_service_discovery` is
+    /// called (e.g. from within one of the listeners), use `start_discovery` to
+    /// discover services without blocking.
     pub fn discover_services(
         &self,
         service_type: &str,
         listeners: DiscoveryListeners,
     ) -> Result<(), Error> {
-        self.adapter.start_discovery(service_type, listeners)
+        let session = self.start_discovery(service_type)?;
+
+        for event in session.iter() {
+            match event {
+                DiscoveryEvent::ServiceDiscovered(service) => {
+                    if let Some(on_service_discovered) = listeners.on_service_discovered {
+                        on_service_discovered(service);
+                    }
+                }
+                DiscoveryEvent::ServiceRemoved(service) => {
+                    if let Some(on_service_removed) = listeners.on_service_removed {
+                        on_service_removed(service);
+                    }
+                }
+                DiscoveryEvent::CacheExhausted => {
+                    if let Some(on_cache_exhausted) = listeners.on_cache_exhausted {
+                        on_cache_exhausted();
+                    }
+                }
+                DiscoveryEvent::AllDiscovered => {
+                    if let Some(on_all_discovered) = listeners.on_all_discovered {
+                        on_all_discovered();
+                    }
+                }
+                DiscoveryEvent::Failed(error) => {
+                    if let Some(on_discovery_failed) = listeners.on_discovery_failed {
+                        on_discovery_failed(error.clone());
+                    }
+
+                    return Err(error);
+                }
+            }
+        }
+
+        Ok(())
     }
 
-    pub fn resolve_service(&self, service: ServiceInfo, listeners: ResolveListeners) {
-        self.adapter.resolve(service, listeners);
+    /// Resolves service and dispatches result to `listeners`. Blocks the calling
+    /// thread until service is resolved, resolution fails or `timeout` elapses.
+    pub fn resolve_service(
+        &self,
+        service: &DiscoveredService,
+        listeners: ResolveListeners,
+        timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        match self.resolve(service, LookupFlags::empty(), timeout) {
+            Ok(service) => {
+                if let Some(on_service_resolved) = listeners.on_service_resolved {
+                    on_service_resolved(service);
+                }
+
+                Ok(())
+            }
+            Err(error) => {
+                if let Some(on_resolve_failed) = listeners.on_resolve_failed {
+                    on_resolve_failed(error.clone());
+                }
+
+                Err(error)
+            }
+        }
+    }
+
+    /// Resolves service using lookup `flags`, e.g. `LookupFlags::NO_TXT` to skip TXT
+    /// record lookup. Blocks the calling thread until service is resolved, resolution
+    /// fails or `timeout` elapses.
+    pub fn resolve_service_with_flags(
+        &self,
+        service: &DiscoveredService,
+        flags: LookupFlags,
+        timeout: Option<Duration>,
+    ) -> Result<ResolvedService, Error> {
+        self.resolve(service, flags, timeout)
+    }
+
+    fn resolve(
+        &self,
+        service: &DiscoveredService,
+        flags: LookupFlags,
+        timeout: Option<Duration>,
+    ) -> Result<ResolvedService, Error> {
+        let (sender, receiver) = mpsc::channel();
+
+        let resolver_id = self.adapter.start_resolve(
+            ServiceInfo::from(service.clone()),
+            ServiceProtocol::Unspecified,
+            flags,
+            Box::new(move |result| {
+                let _ = sender.send(result);
+            }),
+        )?;
+
+        let result = receive_result(&receiver, timeout);
+        self.adapter.stop_resolve(resolver_id);
+
+        result.and_then(ResolvedService::try_from)
+    }
+
+    /// Resolves host name (e.g. `printer.local`) to the address of `protocol` family
+    /// (any family if `Unspecified`). Blocks the calling thread until host name is
+    /// resolved, resolution fails or `timeout` elapses. Only the first address found is
+    /// returned, even if host has addresses of both families, so host name has to be
+    /// resolved with `IPv4` and `IPv6` separately to get both of them.
+    pub fn resolve_host_name(
+        &self,
+        host_name: &str,
+        protocol: ServiceProtocol,
+        timeout: Option<Duration>,
+    ) -> Result<HostInfo, Error> {
+        self.resolve_host_name_with_flags(host_name, protocol, LookupFlags::empty(), timeout)
+    }
+
+    /// Same as `resolve_host_name`, but host name is resolved using `flags`, e.g.
+    /// `LookupFlags::USE_MULTICAST` to ignore wide area DNS.
+    pub fn resolve_host_name_with_flags(
+        &self,
+        host_name: &str,
+        protocol: ServiceProtocol,
+        flags: LookupFlags,
+        timeout: Option<Duration>,
+    ) -> Result<HostInfo, Error> {
+        self.resolve_host_name_with_options(
+            host_name,
+            protocol,
+            DiscoveryOptions::new().flags(flags),
+            timeout,
+        )
+    }
+
+    /// Same as `resolve_host_name`, but host name is resolved on the interface and over
+    /// the protocol specified in `options`, while `protocol` still specifies family of
+    /// the address to look for. `domain` and skip options are ignored.
+    pub fn resolve_host_name_with_options(
+        &self,
+        host_name: &str,
+        protocol: ServiceProtocol,
+        options: DiscoveryOptions,
+        timeout: Option<Duration>,
+    ) -> Result<HostInfo, Error> {
+        let (sender, receiver) = mpsc::channel();
+
+        let resolver_id = self.adapter.start_host_name_resolve(
+            host_name,
+            protocol,
+            &options,
+            Box::new(move |result| {
+                let _ = sender.send(result);
+            }),
+        )?;
+
+        let result = receive_result(&receiver, timeout);
+        self.adapter.stop_host_name_resolve(resolver_id);
+
+        result
+    }
+
+    /// Resolves address to the host name (e.g. `printer.local`). Blocks the calling
+    /// thread until address is resolved, resolution fails or `timeout` elapses.
+    pub fn resolve_address(
+        &self,
+        address: IpAddr,
+        timeout: Option<Duration>,
+    ) -> Result<HostInfo, Error> {
+        self.resolve_address_with_flags(address, LookupFlags::empty(), timeout)
+    }
+
+    /// Same as `resolve_address`, but address is resolved using `flags`.
+    pub fn resolve_address_with_flags(
+        &self,
+        address: IpAddr,
+        flags: LookupFlags,
+        timeout: Option<Duration>,
+    ) -> Result<HostInfo, Error> {
+        self.resolve_address_with_options(address, DiscoveryOptions::new().flags(flags), timeout)
     }
 
+    /// Same as `resolve_address`, but address is resolved on the interface and over the
+    /// protocol specified in `options`. `domain` and skip options are ignored.
+    pub fn resolve_address_with_options(
+        &self,
+        address: IpAddr,
+        options: DiscoveryOptions,
+        timeout: Option<Duration>,
+    ) -> Result<HostInfo, Error> {
+        let (sender, receiver) = mpsc::channel();
+
+        let resolver_id = self.adapter.start_address_resolve(
+            address,
+            &options,
+            Box::new(move |result| {
+                let _ = sender.send(result);
+            }),
+        )?;
+
+        let result = receive_result(&receiver, timeout);
+        self.adapter.stop_address_resolve(resolver_id);
+
+        result
+    }
+
+    /// Resolves service over both IPv4 and IPv6 on every interface it has been seen on
+    /// and merges results into one record that lists every address of the service.
+    /// `services` are discovery results for the same service (usually one per
+    /// interface and protocol).
+    ///
+    /// Host name, port, TXT record, interface and flags of the result come from the
+    /// first resolver that succeeds, the other successful resolvers contribute their
+    /// addresses only (duplicates are dropped). Resolvers of address families the host
+    /// doesn't have never report, so once the first resolver succeeds the rest are
+    /// given `AGGREGATION_GRACE_PERIOD` to report and then stopped. Blocks the calling
+    /// thread until every resolver has reported, the grace period is over or `timeout`
+    /// elapses, fails with the last resolver error if none of them succeeds.
+    pub fn resolve_service_aggregated(
+        &self,
+        services: &[DiscoveredService],
+        timeout: Duration,
+    ) -> Result<ResolvedService, Error> {
+        let service = services
+            .first()
+            .ok_or_else(|| Error::Internal("There is no service to resolve".to_owned()))?;
+
+        let mut interfaces: Vec<i32> = services.iter().map(|service| service.interface).collect();
+        interfaces.sort();
+        interfaces.dedup();
+
+        let (sender, receiver) = mpsc::channel();
+        let mut resolver_ids = Vec::new();
+
+        for interface in interfaces {
+            for address_protocol in &[ServiceProtocol::IPv4, ServiceProtocol::IPv6] {
+                let resolver_index = resolver_ids.len();
+                let sender = sender.clone();
+
+                let result = self.adapter.start_resolve(
+                    ServiceInfo {
+                        address: None,
+                        addresses: Vec::new(),
+                        domain: Some(service.domain.clone()),
+                        host_name: None,
+                        interface,
+                        name: Some(service.name.clone()),
+                        port: 0,
+                        protocol: ServiceProtocol::Unspecified,
+                        txt: None,
+                        type_name: Some(service.type_name.clone()),
+                        flags: LookupResultFlags::empty(),
+                    },
+                    *address_protocol,
+                    LookupFlags::empty(),
+                    Box::new(move |result| {
+                        let _ = sender.send((resolver_index, result));
+                    }),
+                );
+
+                match result {
+                    Ok(resolver_id) => resolver_ids.push(resolver_id),
+                    Err(error) => {
+                        for resolver_id in resolver_ids {
+                            self.adapter.stop_resolve(resolver_id);
+                        }
+
+                        return Err(error);
+                    }
+                }
+            }
+        }
+
+        let mut deadline = Instant::now() + timeout;
+        let mut reported = vec![false; resolver_ids.len()];
+        let mut resolved_service: Option<ServiceInfo> = None;
+        let mut last_error = None;
+
+        while reported.contains(&false) {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            match receiver.recv_timeout(deadline - now) {
+                Ok((resolver_index, Ok(service))) => {
+                    reported[resolver_index] = true;
+
+                    match resolved_service {
+                        Some(ref mut resolved_service) => {
+                            for address in service.addresses {
+                                if !resolved_service.addresses.contains(&address) {
+                                    resolved_service.addresses.push(address);
+                                }
+                            }
+                        }
+                        None => {
+                            resolved_service = Some(service);
+                            deadline = deadline.min(Instant::now() + AGGREGATION_GRACE_PERIOD);
+                        }
+                    }
+                }
+                Ok((resolver_index, Err(error))) => {
+                    reported[resolver_index] = true;
+                    last_error = Some(error);
+                }
+                Err(_) => break,
+            }
+        }
+
+        for resolver_id in resolver_ids {
+            self.adapter.stop_resolve(resolver_id);
+        }
+
+        resolved_service
+            .ok_or_else(|| {
+                last_error.unwrap_or_else(|| {
+                    Error::Timeout("Service resolution has timed out".to_owned())
+                })
+            })
+            .and_then(ResolvedService::try_from)
+    }
+
+    /// Stops all discovery sessions started with this manager.
     pub fn stop_service_discovery(&self) {
-        self.adapter.stop_discovery();
+        self.adapter.stop_all_discovery();
+    }
+}
+
+/// Waits for the first result reported by resolver, `timeout` of `None` means forever.
+fn receive_result<T>(
+    receiver: &mpsc::Receiver<Result<T, Error>>,
+    timeout: Option<Duration>,
+) -> Result<T, Error> {
+    let result = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout).map_err(|error| match error {
+            mpsc::RecvTimeoutError::Timeout => {
+                Error::Timeout("Resolution has timed out".to_owned())
+            }
+            mpsc::RecvTimeoutError::Disconnected => {
+                Error::Internal("Resolver has been stopped".to_owned())
+            }
+        }),
+        None => receiver
+            .recv()
+            .map_err(|_| Error::Internal("Resolver has been stopped".to_owned())),
+    };
+
+    result.and_then(|result| result)
+}
+
+/// Builds options for the `*_with_flags` methods that only take domain and flags.
+fn options_with_flags(domain: Option<&str>, flags: LookupFlags) -> DiscoveryOptions {
+    let options = DiscoveryOptions::new().flags(flags);
+
+    match domain {
+        Some(domain) => options.domain(domain),
+        None => options,
     }
 }
 
 impl Default for DiscoveryManager {
     fn default() -> Self {
-        let adapter: Box<dyn DiscoveryAdapter> = Box::new(PlatformDependentAdapter::new());
+        let adapter: Arc<dyn DiscoveryAdapter> = Arc::new(PlatformDependentAdapter::new());
 
         DiscoveryManager { adapter }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use host::host_manager::HostManager;
+
+    #[test]
+    fn reports_service_removal_to_listener() {
+        let fake = FakeAdapter::default();
+        let manager = DiscoveryManager::with_fake_adapter(fake.clone());
+        let removed = RefCell::new(Vec::new());
+
+        let on_all_discovered = || fake.remove_service("fake", "_http._tcp");
+        let on_service_removed = |service: DiscoveredService| {
+            removed.borrow_mut().push(service.name);
+            manager.stop_service_discovery();
+        };
+
+        let listeners = DiscoveryListeners {
+            on_service_removed: Some(&on_service_removed),
+            on_all_discovered: Some(&on_all_discovered),
+            ..Default::default()
+        };
+
+        manager.discover_services("_http._tcp", listeners).unwrap();
+
+        assert_eq!(*removed.borrow(), vec!["fake".to_owned()]);
+    }
+
+    #[test]
+    fn runs_independent_sessions_simultaneously() {
+        let fake = FakeAdapter::default();
+        let manager = DiscoveryManager::with_fake_adapter(fake.clone());
+        let names = |session: &DiscoverySession| -> Vec<String> {
+            session
+                .try_iter()
+                .filter_map(|event| match event {
+                    DiscoveryEvent::ServiceDiscovered(service) => Some(service.name),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let http = manager.start_discovery("_http._tcp").unwrap();
+        let device_info = manager.start_discovery("_device-info._tcp").unwrap();
+        assert_ne!(http.id(), device_info.id());
+        assert_eq!(names(&http), vec!["fake"]);
+        assert!(names(&device_info).is_empty());
+
+        fake.add_service("Printer", "_device-info._tcp");
+        assert!(names(&http).is_empty());
+        assert_eq!(names(&device_info), vec!["Printer"]);
+
+        // Stopping one session leaves the other one running.
+        http.stop();
+        fake.add_service("Scanner", "_device-info._tcp");
+        assert_eq!(names(&device_info), vec!["Scanner"]);
+    }
+
+    #[test]
+    fn reports_resolution_failures_and_timeouts() {
+        let fake = FakeAdapter::default();
+        let manager = DiscoveryManager::with_fake_adapter(fake.clone());
+        let service = |name: &str| DiscoveredService {
+            name: name.to_owned(),
+            type_name: "_http._tcp".to_owned(),
+            domain: "local".to_owned(),
+            interface: 1,
+            protocol: ServiceProtocol::IPv4,
+            flags: LookupResultFlags::MULTICAST,
+        };
+        let failures = RefCell::new(Vec::new());
+        let on_resolve_failed = |error: Error| failures.borrow_mut().push(error);
+        let listeners = || ResolveListeners {
+            on_resolve_failed: Some(&on_resolve_failed),
+            ..Default::default()
+        };
+
+        manager
+            .resolve_service(&service("fake"), listeners(), None)
+            .unwrap();
+        assert!(failures.borrow().is_empty());
+
+        let result = manager.resolve_service(&service("unknown"), listeners(), None);
+        assert!(match result {
+            Err(Error::AdapterFailure(_)) => true,
+            _ => false,
+        });
+
+        // Host of the service is gone, so its address is never found.
+        fake.remove_host("fake.local");
+        let result = manager.resolve_service(
+            &service("fake"),
+            listeners(),
+            Some(Duration::from_millis(50)),
+        );
+        assert!(match result {
+            Err(Error::Timeout(_)) => true,
+            _ => false,
+        });
+
+        assert_eq!(failures.borrow().len(), 2);
+    }
+
+    #[test]
+    fn uses_interface_as_scope_id_for_link_local_addresses_only() {
+        let service = |address: &str| ServiceInfo {
+            address: Some(address.parse().unwrap()),
+            addresses: Vec::new(),
+            domain: None,
+            host_name: None,
+            interface: 3,
+            name: None,
+            port: 80,
+            protocol: ServiceProtocol::IPv6,
+            type_name: None,
+            txt: None,
+            flags: LookupResultFlags::empty(),
+        };
+
+        assert_eq!(
+            service("fe80::1").socket_addr(),
+            Some("[fe80::1%3]:80".parse().unwrap())
+        );
+        assert_eq!(
+            service("fd00::1").socket_addr(),
+            Some("[fd00::1]:80".parse().unwrap())
+        );
+        assert_eq!(
+            service("2001:db8::1").socket_addr(),
+            Some("[2001:db8::1]:80".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn scopes_resolved_link_local_addresses_to_service_interface() {
+        let fake = FakeAdapter::default();
+        let manager = DiscoveryManager::with_fake_adapter(fake.clone());
+        let service = DiscoveredService {
+            name: "fake".to_owned(),
+            type_name: "_http._tcp".to_owned(),
+            domain: "local".to_owned(),
+            interface: 3,
+            protocol: ServiceProtocol::IPv6,
+            flags: LookupResultFlags::MULTICAST,
+        };
+
+        fake.remove_host("fake.local");
+        fake.add_host("fake.local", "fe80::1".parse().unwrap());
+        let resolved = manager
+            .resolve_service_with_flags(&service, LookupFlags::empty(), None)
+            .unwrap();
+        assert_eq!(resolved.addresses, vec!["[fe80::1%3]:80".parse().unwrap()]);
+
+        fake.remove_host("fake.local");
+        fake.add_host("fake.local", "2001:db8::1".parse().unwrap());
+        let resolved = manager
+            .resolve_service_with_flags(&service, LookupFlags::empty(), None)
+            .unwrap();
+        assert_eq!(
+            resolved.addresses,
+            vec!["[2001:db8::1]:80".parse().unwrap()]
+        );
+
+        let resolved = manager
+            .resolve_service_with_flags(&service, LookupFlags::NO_ADDRESS, None)
+            .unwrap();
+        assert!(resolved.addresses.is_empty());
+    }
+
+    #[test]
+    fn aggregates_addresses_without_waiting_for_missing_families() {
+        let fake = FakeAdapter::default();
+        let manager = DiscoveryManager::with_fake_adapter(fake.clone());
+        let service = |name: &str| DiscoveredService {
+            name: name.to_owned(),
+            type_name: "_http._tcp".to_owned(),
+            domain: "local".to_owned(),
+            interface: 2,
+            protocol: ServiceProtocol::IPv4,
+            flags: LookupResultFlags::MULTICAST,
+        };
+
+        let started = Instant::now();
+        let resolved = manager
+            .resolve_service_aggregated(&[service("fake")], Duration::from_secs(30))
+            .unwrap();
+        assert!(started.elapsed() < AGGREGATION_GRACE_PERIOD);
+        assert_eq!(
+            resolved.addresses,
+            vec![
+                "192.168.1.1:80".parse().unwrap(),
+                "[fe80::1%2]:80".parse().unwrap()
+            ]
+        );
+
+        fake.remove_host("fake.local");
+        fake.add_host("fake.local", "192.168.1.1".parse().unwrap());
+
+        let started = Instant::now();
+        let resolved = manager
+            .resolve_service_aggregated(&[service("fake")], Duration::from_secs(30))
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(resolved.host_name, "fake.local");
+        assert_eq!(resolved.addresses, vec!["192.168.1.1:80".parse().unwrap()]);
+
+        let result =
+            manager.resolve_service_aggregated(&[service("unknown")], Duration::from_secs(30));
+        assert!(match result {
+            Err(Error::AdapterFailure(_)) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    fn lists_advertised_and_registered_service_types() {
+        let fake = FakeAdapter::default();
+        let host_manager = HostManager::with_fake_adapter(fake.clone());
+        let manager = host_manager.discovery_manager();
+
+        fake.add_service("Printer", "_ipp._tcp");
+        fake.add_service("Other", "_http._tcp");
+        let _service = host_manager
+            .announce_service("Me", "_device-info._tcp", 0)
+            .unwrap();
+
+        let discover = |options: DiscoveryOptions| -> Vec<ServiceTypeInfo> {
+            let session = manager
+                .discover_service_types_with_options(options)
+                .unwrap();
+            let mut service_types = Vec::new();
+
+            for event in session.iter() {
+                match event {
+                    ServiceTypeEvent::ServiceTypeDiscovered(info) => service_types.push(info),
+                    ServiceTypeEvent::AllDiscovered => break,
+                    _ => {}
+                }
+            }
+
+            service_types
+        };
+
+        let service_types = discover(DiscoveryOptions::new());
+        assert_eq!(
+            service_types
+                .iter()
+                .map(|info| info.service_type.as_str())
+                .collect::<Vec<_>>(),
+            vec!["_device-info._tcp", "_http._tcp", "_ipp._tcp"]
+        );
+        assert!(service_types.iter().all(|info| info.domain == "local"));
+
+        let service_types = discover(DiscoveryOptions::new().domain("example.com"));
+        assert!(service_types
+            .iter()
+            .all(|info| info.domain == "example.com"));
+
+        assert!(discover(DiscoveryOptions::new().interface(2)).is_empty());
+    }
+
+    #[test]
+    fn browses_services_in_discovered_domains() {
+        let manager = DiscoveryManager::with_fake_adapter(FakeAdapter::default());
+        let discover = |options: DiscoveryOptions| -> Vec<String> {
+            manager
+                .discover_domains_with_options(DomainType::Browse, options)
+                .unwrap()
+                .iter()
+                .take_while(|event| match event {
+                    DomainEvent::AllDiscovered => false,
+                    _ => true,
+                })
+                .filter_map(|event| match event {
+                    DomainEvent::DomainDiscovered(info) => Some(info.domain),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        assert!(discover(DiscoveryOptions::new().interface(2)).is_empty());
+
+        let domains = discover(DiscoveryOptions::new());
+        assert_eq!(domains, vec!["local"]);
+
+        let session = manager
+            .start_discovery_in_domain("_http._tcp", Some(&domains[0]))
+            .unwrap();
+        let service = session
+            .iter()
+            .filter_map(|event| match event {
+                DiscoveryEvent::ServiceDiscovered(service) => Some(service),
+                _ => None,
+            })
+            .next()
+            .unwrap();
+        assert_eq!(service.domain, domains[0]);
+    }
+
+    #[test]
+    fn skips_own_and_local_host_services_on_request() {
+        let fake = FakeAdapter::default();
+        let host_manager = HostManager::with_fake_adapter(fake.clone());
+        let manager = host_manager.discovery_manager();
+
+        fake.add_local_service("Peer", "_http._tcp");
+        let _service = host_manager
+            .announce_service("Me", "_http._tcp", 80)
+            .unwrap();
+
+        let discover = |options: DiscoveryOptions| -> Vec<DiscoveredService> {
+            let session = manager
+                .start_discovery_with_options("_http._tcp", options)
+                .unwrap();
+
+            session
+                .try_iter()
+                .filter_map(|event| match event {
+                    DiscoveryEvent::ServiceDiscovered(service) => Some(service),
+                    _ => None,
+                })
+                .collect()
+        };
+        let names = |services: Vec<DiscoveredService>| -> Vec<String> {
+            services.into_iter().map(|service| service.name).collect()
+        };
+
+        let services = discover(DiscoveryOptions::new());
+        assert_eq!(names(services.clone()), vec!["fake", "Peer", "Me"]);
+        assert!(!services[0].is_local());
+        assert!(services[1].is_local() && !services[1].is_our_own());
+        assert!(services[2].is_local() && services[2].is_our_own());
+
+        let services = discover(DiscoveryOptions::new().skip_our_own(true));
+        assert_eq!(names(services), vec!["fake", "Peer"]);
+
+        let services = discover(DiscoveryOptions::new().skip_local_host(true));
+        assert_eq!(names(services), vec!["fake"]);
+    }
+}
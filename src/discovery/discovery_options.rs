@@ -0,0 +1,119 @@
+use std::ffi::CString;
+
+use libc::if_nametoindex;
+
+use adapters::errors::Error;
+use discovery::discovery_manager::{DiscoveryEvent, ServiceProtocol};
+use discovery::lookup_flags::{LookupFlags, LookupResultFlags};
+
+/// Describes where services should be looked for. Use `new` to create options that
+/// cover all interfaces, both IP families and the default domain, and builder methods
+/// to narrow discovery down.
+/// The same options are taken by service type, domain and record browsers and by host
+/// name and address resolvers, each of them ignores the options that don't apply.
+#[derive(Clone, Debug)]
+pub struct DiscoveryOptions {
+    /// Index of the network interface to browse on, `-1` means all interfaces.
+    pub interface: i32,
+    /// Protocol to browse over, `Unspecified` means both IPv4 and IPv6.
+    pub protocol: ServiceProtocol,
+    /// Domain to browse in, `None` means default domain (usually `local`).
+    pub domain: Option<String>,
+    /// Flags that control lookup, empty by default.
+    pub flags: LookupFlags,
+    /// Whether services published by any process of the local host should be left out.
+    pub skip_local_host: bool,
+    /// Whether services registered through the same adapter should be left out.
+    pub skip_our_own: bool,
+}
+
+impl DiscoveryOptions {
+    pub fn new() -> Self {
+        DiscoveryOptions {
+            interface: -1,
+            protocol: ServiceProtocol::Unspecified,
+            domain: None,
+            flags: LookupFlags::empty(),
+            skip_local_host: false,
+            skip_our_own: false,
+        }
+    }
+
+    pub fn interface(mut self, interface: i32) -> Self {
+        self.interface = interface;
+        self
+    }
+
+    /// Restricts discovery to the network interface with the specified name (e.g.
+    /// `br0`), fails if there is no such interface.
+    pub fn interface_name(self, name: &str) -> Result<Self, Error> {
+        Ok(self.interface(interface_index(name)?))
+    }
+
+    pub fn protocol(mut self, protocol: ServiceProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_owned());
+        self
+    }
+
+    /// Sets lookup flags, e.g. `LookupFlags::USE_MULTICAST` to ignore wide area DNS.
+    pub fn flags(mut self, flags: LookupFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Leaves out services published by the local host, including the ones published
+    /// by other processes (e.g. another instance of the application). Use
+    /// `skip_our_own` to leave out own registrations only. Only service discovery
+    /// honours it.
+    pub fn skip_local_host(mut self, skip_local_host: bool) -> Self {
+        self.skip_local_host = skip_local_host;
+        self
+    }
+
+    /// Leaves out services registered with the `HostManager` that discovery manager
+    /// has been obtained from with `HostManager::discovery_manager`, so that peers of
+    /// the same type don't discover themselves. Managers created separately don't
+    /// share the connection to the mDNS daemon, so nothing is left out for them. Only
+    /// service discovery honours it.
+    pub fn skip_our_own(mut self, skip_our_own: bool) -> Self {
+        self.skip_our_own = skip_our_own;
+        self
+    }
+
+    /// Returns `true` if the service discovery event should be left out.
+    pub(crate) fn skips(&self, event: &DiscoveryEvent) -> bool {
+        let flags = match *event {
+            DiscoveryEvent::ServiceDiscovered(ref service)
+            | DiscoveryEvent::ServiceRemoved(ref service) => service.flags,
+            _ => return false,
+        };
+
+        (self.skip_local_host && flags.contains(LookupResultFlags::LOCAL))
+            || (self.skip_our_own && flags.contains(LookupResultFlags::OUR_OWN))
+    }
+}
+
+impl Default for DiscoveryOptions {
+    fn default() -> Self {
+        DiscoveryOptions::new()
+    }
+}
+
+/// Returns index of the network interface with the specified name (e.g. `eth0`).
+pub(crate) fn interface_index(name: &str) -> Result<i32, Error> {
+    let c_name = CString::new(name)
+        .map_err(|_| Error::Internal("Interface name contains NUL byte".to_owned()))?;
+
+    match unsafe { if_nametoindex(c_name.as_ptr()) } {
+        0 => Err(Error::Internal(format!(
+            "Unknown network interface {:?}",
+            name
+        ))),
+        index => Ok(index as i32),
+    }
+}
@@ -0,0 +1,641 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use adapters::errors::Error;
+use discovery::txt_record::{TxtEntry, TxtRecord};
+
+/// Maximum length of the single domain name label, see RFC 1035, section 2.3.4.
+const MAX_LABEL_LENGTH: usize = 63;
+/// Maximum length of the domain name in the wire format.
+const MAX_NAME_LENGTH: usize = 255;
+/// Maximum length of the `<character-string>`, e.g. single TXT record entry.
+const MAX_CHARACTER_STRING_LENGTH: usize = 255;
+
+/// The only DNS class used by multicast DNS (Internet).
+pub const CLASS_IN: u16 = 1;
+
+/// Type of the DNS resource record (see RFC 1035, section 3.2.2).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RecordType {
+    A,
+    Ns,
+    Cname,
+    Soa,
+    Ptr,
+    Hinfo,
+    Mx,
+    Txt,
+    Aaaa,
+    Srv,
+    /// Any other record type identified by its numeric value.
+    Other(u16),
+}
+
+impl RecordType {
+    pub fn from_u16(value: u16) -> RecordType {
+        match value {
+            1 => RecordType::A,
+            2 => RecordType::Ns,
+            5 => RecordType::Cname,
+            6 => RecordType::Soa,
+            12 => RecordType::Ptr,
+            13 => RecordType::Hinfo,
+            15 => RecordType::Mx,
+            16 => RecordType::Txt,
+            28 => RecordType::Aaaa,
+            33 => RecordType::Srv,
+            value => RecordType::Other(value),
+        }
+    }
+
+    pub fn to_u16(self) -> u16 {
+        match self {
+            RecordType::A => 1,
+            RecordType::Ns => 2,
+            RecordType::Cname => 5,
+            RecordType::Soa => 6,
+            RecordType::Ptr => 12,
+            RecordType::Hinfo => 13,
+            RecordType::Mx => 15,
+            RecordType::Txt => 16,
+            RecordType::Aaaa => 28,
+            RecordType::Srv => 33,
+            RecordType::Other(value) => value,
+        }
+    }
+}
+
+/// Decoded data (rdata) of the DNS resource record.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RecordData {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Ns(String),
+    Cname(String),
+    Ptr(String),
+    Txt(TxtRecord),
+    Srv {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    Hinfo {
+        cpu: String,
+        os: String,
+    },
+    Mx {
+        preference: u16,
+        exchange: String,
+    },
+    /// Data of the record type that isn't supported or couldn't be decoded.
+    Raw {
+        record_type: RecordType,
+        data: Vec<u8>,
+    },
+}
+
+impl RecordData {
+    /// Decodes rdata in the DNS wire format. Data that can't be decoded as
+    /// `record_type` is returned as `Raw`.
+    pub fn decode(record_type: RecordType, data: &[u8]) -> RecordData {
+        RecordData::try_decode(record_type, data).unwrap_or_else(|_| RecordData::Raw {
+            record_type,
+            data: data.to_vec(),
+        })
+    }
+
+    /// Same as `decode`, but fails for the record types that aren't supported and for
+    /// the data that is truncated or has trailing bytes.
+    pub fn try_decode(record_type: RecordType, data: &[u8]) -> Result<RecordData, Error> {
+        RecordData::decode_data(record_type, data).ok_or_else(|| {
+            Error::Internal(format!("Data can't be decoded as {:?} record", record_type))
+        })
+    }
+
+    /// Returns type of the record this data belongs to.
+    pub fn record_type(&self) -> RecordType {
+        match *self {
+            RecordData::A(_) => RecordType::A,
+            RecordData::Aaaa(_) => RecordType::Aaaa,
+            RecordData::Ns(_) => RecordType::Ns,
+            RecordData::Cname(_) => RecordType::Cname,
+            RecordData::Ptr(_) => RecordType::Ptr,
+            RecordData::Txt(_) => RecordType::Txt,
+            RecordData::Srv { .. } => RecordType::Srv,
+            RecordData::Hinfo { .. } => RecordType::Hinfo,
+            RecordData::Mx { .. } => RecordType::Mx,
+            RecordData::Raw { record_type, .. } => record_type,
+        }
+    }
+
+    /// Encodes data into the DNS wire format. Domain names may contain dots and
+    /// backslashes escaped with backslash, names with too long labels and character
+    /// strings longer than 255 bytes are rejected.
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut writer = Writer { data: Vec::new() };
+
+        match *self {
+            RecordData::A(ref address) => writer.data.extend_from_slice(&address.octets()),
+            RecordData::Aaaa(ref address) => writer.data.extend_from_slice(&address.octets()),
+            RecordData::Ns(ref name) | RecordData::Cname(ref name) | RecordData::Ptr(ref name) => {
+                writer.write_name(name)?
+            }
+            RecordData::Txt(ref txt) => {
+                for entry in txt.iter() {
+                    writer.write_character_string(&entry.to_bytes())?;
+                }
+
+                // Empty TXT record must contain a single empty string, see RFC 6763.
+                if txt.is_empty() {
+                    writer.write_character_string(&[])?;
+                }
+            }
+            RecordData::Srv {
+                priority,
+                weight,
+                port,
+                ref target,
+            } => {
+                writer.write_u16(priority);
+                writer.write_u16(weight);
+                writer.write_u16(port);
+                writer.write_name(target)?;
+            }
+            RecordData::Hinfo { ref cpu, ref os } => {
+                writer.write_character_string(cpu.as_bytes())?;
+                writer.write_character_string(os.as_bytes())?;
+            }
+            RecordData::Mx {
+                preference,
+                ref exchange,
+            } => {
+                writer.write_u16(preference);
+                writer.write_name(exchange)?;
+            }
+            RecordData::Raw { ref data, .. } => writer.data.extend_from_slice(data),
+        }
+
+        Ok(writer.data)
+    }
+
+    fn decode_data(record_type: RecordType, data: &[u8]) -> Option<RecordData> {
+        let mut reader = Reader { data, position: 0 };
+
+        let record_data = match record_type {
+            RecordType::A => {
+                let octets = reader.read_bytes(4)?;
+                RecordData::A(Ipv4Addr::new(octets[0], octets[1], octets[2], octets[3]))
+            }
+            RecordType::Aaaa => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(reader.read_bytes(16)?);
+                RecordData::Aaaa(Ipv6Addr::from(octets))
+            }
+            RecordType::Ns => RecordData::Ns(reader.read_name()?),
+            RecordType::Cname => RecordData::Cname(reader.read_name()?),
+            RecordType::Ptr => RecordData::Ptr(reader.read_name()?),
+            RecordType::Txt => {
+                let mut txt = TxtRecord::new();
+                while !reader.is_empty() {
+                    if let Some(entry) = TxtEntry::from_bytes(reader.read_character_string()?) {
+                        txt.push(entry);
+                    }
+                }
+                RecordData::Txt(txt)
+            }
+            RecordType::Srv => RecordData::Srv {
+                priority: reader.read_u16()?,
+                weight: reader.read_u16()?,
+                port: reader.read_u16()?,
+                target: reader.read_name()?,
+            },
+            RecordType::Hinfo => RecordData::Hinfo {
+                cpu: String::from_utf8_lossy(reader.read_character_string()?).into_owned(),
+                os: String::from_utf8_lossy(reader.read_character_string()?).into_owned(),
+            },
+            RecordType::Mx => RecordData::Mx {
+                preference: reader.read_u16()?,
+                exchange: reader.read_name()?,
+            },
+            RecordType::Soa | RecordType::Other(_) => return None,
+        };
+
+        // Trailing bytes mean that data doesn't match the record type.
+        if reader.is_empty() {
+            Some(record_data)
+        } else {
+            None
+        }
+    }
+}
+
+/// Reads DNS wire format primitives, all methods return `None` if data is too short.
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    fn read_bytes(&mut self, length: usize) -> Option<&'a [u8]> {
+        if self.data.len() - self.position < length {
+            return None;
+        }
+
+        let bytes = &self.data[self.position..self.position + length];
+        self.position += length;
+        Some(bytes)
+    }
+
+    fn read_u16(&mut self) -> Option<u16> {
+        self.read_bytes(2)
+            .map(|bytes| u16::from(bytes[0]) << 8 | u16::from(bytes[1]))
+    }
+
+    fn read_character_string(&mut self) -> Option<&'a [u8]> {
+        let length = self.read_bytes(1)?[0] as usize;
+        self.read_bytes(length)
+    }
+
+    /// Reads uncompressed domain name in the form `write_name` accepts: dots and
+    /// backslashes within labels are escaped with backslash, control characters and
+    /// bytes that aren't valid UTF-8 are escaped as decimal `\DDD` byte value.
+    fn read_name(&mut self) -> Option<String> {
+        let start = self.position;
+        let mut name = String::new();
+
+        loop {
+            let length = self.read_bytes(1)?[0] as usize;
+            if length == 0 {
+                break;
+            }
+
+            // Compression pointers are not expected in the data Avahi provides.
+            if length > MAX_LABEL_LENGTH {
+                return None;
+            }
+
+            if !name.is_empty() {
+                name.push('.');
+            }
+
+            escape_label(self.read_bytes(length)?, &mut name);
+        }
+
+        if self.position - start > MAX_NAME_LENGTH {
+            return None;
+        }
+
+        Some(name)
+    }
+}
+
+/// Appends label to the name escaping it the way `Writer::write_name` unescapes it.
+fn escape_label(label: &[u8], name: &mut String) {
+    match ::std::str::from_utf8(label) {
+        Ok(label) => {
+            for character in label.chars() {
+                match character {
+                    '.' | '\\' => {
+                        name.push('\\');
+                        name.push(character);
+                    }
+                    character if character.is_ascii_control() => {
+                        name.push_str(&format!("\\{:03}", character as u8))
+                    }
+                    character => name.push(character),
+                }
+            }
+        }
+        Err(_) => {
+            for &byte in label {
+                match byte {
+                    b'.' | b'\\' => {
+                        name.push('\\');
+                        name.push(byte as char);
+                    }
+                    0x20..=0x7E => name.push(byte as char),
+                    byte => name.push_str(&format!("\\{:03}", byte)),
+                }
+            }
+        }
+    }
+}
+
+/// Writes DNS wire format primitives.
+struct Writer {
+    data: Vec<u8>,
+}
+
+impl Writer {
+    fn write_u16(&mut self, value: u16) {
+        self.data.push((value >> 8) as u8);
+        self.data.push(value as u8);
+    }
+
+    fn write_character_string(&mut self, value: &[u8]) -> Result<(), Error> {
+        if value.len() > MAX_CHARACTER_STRING_LENGTH {
+            return Err(Error::Internal(format!(
+                "Character string is longer than {} bytes",
+                MAX_CHARACTER_STRING_LENGTH
+            )));
+        }
+
+        self.data.push(value.len() as u8);
+        self.data.extend_from_slice(value);
+
+        Ok(())
+    }
+
+    /// Writes uncompressed domain name, trailing dot is optional. Backslash escapes
+    /// either the next character or the decimal `\DDD` byte value, like in Avahi.
+    fn write_name(&mut self, name: &str) -> Result<(), Error> {
+        let name = if has_root_dot(name) {
+            &name[..name.len() - 1]
+        } else {
+            name
+        };
+
+        let mut labels = vec![];
+        if !name.is_empty() {
+            let mut label = vec![];
+            let mut bytes = name.bytes();
+
+            while let Some(byte) = bytes.next() {
+                match byte {
+                    b'.' => labels.push(label.split_off(0)),
+                    b'\\' => {
+                        let escaped = bytes.next().ok_or_else(|| invalid_name(name))?;
+                        if escaped.is_ascii_digit() {
+                            let digits = [
+                                escaped,
+                                bytes.next().unwrap_or(0),
+                                bytes.next().unwrap_or(0),
+                            ];
+                            if !digits.iter().all(u8::is_ascii_digit) {
+                                return Err(invalid_name(name));
+                            }
+
+                            let value = digits
+                                .iter()
+                                .fold(0u32, |value, digit| value * 10 + u32::from(digit - b'0'));
+                            if value > 255 {
+                                return Err(invalid_name(name));
+                            }
+                            label.push(value as u8);
+                        } else {
+                            label.push(escaped);
+                        }
+                    }
+                    byte => label.push(byte),
+                }
+            }
+            labels.push(label);
+        }
+
+        let start = self.data.len();
+        for label in labels {
+            if label.is_empty() || label.len() > MAX_LABEL_LENGTH {
+                return Err(invalid_name(name));
+            }
+
+            self.data.push(label.len() as u8);
+            self.data.extend_from_slice(&label);
+        }
+        self.data.push(0);
+
+        if self.data.len() - start > MAX_NAME_LENGTH {
+            return Err(invalid_name(name));
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks whether name ends with the dot that stands for the root rather than with the
+/// escaped one, dot is escaped only if it's preceded by odd number of backslashes.
+fn has_root_dot(name: &str) -> bool {
+    if !name.ends_with('.') {
+        return false;
+    }
+
+    let backslashes = name[..name.len() - 1]
+        .bytes()
+        .rev()
+        .take_while(|byte| *byte == b'\\')
+        .count();
+
+    backslashes % 2 == 0
+}
+
+fn invalid_name(name: &str) -> Error {
+    Error::Internal(format!("{:?} is not a valid domain name", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_name(name: &str) -> Result<Vec<u8>, Error> {
+        RecordData::Ptr(name.to_owned()).encode()
+    }
+
+    #[test]
+    fn encodes_names_with_escapes() {
+        assert_eq!(
+            encode_name("a\\.b.local").unwrap(),
+            b"\x03a.b\x05local\x00".to_vec()
+        );
+        assert_eq!(
+            encode_name("a\\\\b.local").unwrap(),
+            b"\x03a\\b\x05local\x00".to_vec()
+        );
+        assert_eq!(
+            encode_name("a\\032b.local.").unwrap(),
+            b"\x03a b\x05local\x00".to_vec()
+        );
+        assert!(encode_name("a\\25").is_err());
+        assert!(encode_name("a\\256").is_err());
+        assert!(encode_name("a\\").is_err());
+        assert!(encode_name("a..local").is_err());
+    }
+
+    #[test]
+    fn tells_root_dot_from_escaped_one() {
+        // Escaped backslash followed by the root dot.
+        assert_eq!(encode_name("foo\\\\.").unwrap(), b"\x04foo\\\x00".to_vec());
+        // Escaped dot that is the last character of the label.
+        assert_eq!(encode_name("foo\\.").unwrap(), b"\x04foo.\x00".to_vec());
+        // Escaped backslash followed by the escaped dot.
+        assert_eq!(
+            encode_name("foo\\\\\\.").unwrap(),
+            b"\x05foo\\.\x00".to_vec()
+        );
+        assert_eq!(
+            encode_name("local.").unwrap(),
+            encode_name("local").unwrap()
+        );
+    }
+
+    #[test]
+    fn limits_label_and_name_length() {
+        let label = "a".repeat(MAX_LABEL_LENGTH);
+        assert!(encode_name(&label).is_ok());
+        assert!(encode_name(&format!("{}a", label)).is_err());
+
+        // Three 63 byte labels and 61 byte label take exactly 255 bytes on the wire.
+        let name = format!("{0}.{0}.{0}.{1}", label, "a".repeat(61));
+        assert_eq!(encode_name(&name).unwrap().len(), MAX_NAME_LENGTH);
+        assert!(encode_name(&format!("{}a", name)).is_err());
+    }
+
+    #[test]
+    fn round_trips_names() {
+        let names = [
+            "printer.local",
+            "a\\.b.local",
+            "back\\\\slash.local",
+            "foo\\\\",
+            &"a".repeat(MAX_LABEL_LENGTH),
+        ];
+
+        for name in names.iter() {
+            let record = RecordData::Ptr(name.to_string());
+            let data = record.encode().unwrap();
+
+            assert_eq!(RecordData::decode(RecordType::Ptr, &data), record);
+        }
+    }
+
+    #[test]
+    fn decodes_known_records() {
+        assert_eq!(
+            RecordData::try_decode(RecordType::A, &[192, 168, 1, 5]).unwrap(),
+            RecordData::A(Ipv4Addr::new(192, 168, 1, 5))
+        );
+
+        let mut octets = [0; 16];
+        octets[0] = 0xfe;
+        octets[1] = 0x80;
+        octets[15] = 1;
+        assert_eq!(
+            RecordData::try_decode(RecordType::Aaaa, &octets).unwrap(),
+            RecordData::Aaaa("fe80::1".parse().unwrap())
+        );
+
+        assert_eq!(
+            RecordData::try_decode(RecordType::Ptr, b"\x07printer\x05local\x00").unwrap(),
+            RecordData::Ptr("printer.local".to_owned())
+        );
+
+        assert_eq!(
+            RecordData::try_decode(
+                RecordType::Srv,
+                b"\x00\x01\x00\x02\x02\x77\x07printer\x05local\x00"
+            )
+            .unwrap(),
+            RecordData::Srv {
+                priority: 1,
+                weight: 2,
+                port: 631,
+                target: "printer.local".to_owned(),
+            }
+        );
+
+        let mut txt = TxtRecord::new();
+        txt.insert("model", "Xserve").unwrap();
+        txt.insert_flag("color").unwrap();
+        assert_eq!(
+            RecordData::try_decode(RecordType::Txt, b"\x0cmodel=Xserve\x05color\x00").unwrap(),
+            RecordData::Txt(txt)
+        );
+
+        assert_eq!(
+            RecordData::try_decode(RecordType::Mx, b"\x00\x0a\x04mail\x05local\x00").unwrap(),
+            RecordData::Mx {
+                preference: 10,
+                exchange: "mail.local".to_owned(),
+            }
+        );
+
+        assert_eq!(
+            RecordData::try_decode(RecordType::Hinfo, b"\x06x86_64\x05Linux").unwrap(),
+            RecordData::Hinfo {
+                cpu: "x86_64".to_owned(),
+                os: "Linux".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn fails_to_decode_truncated_data() {
+        let truncated: [(RecordType, &[u8]); 6] = [
+            (RecordType::A, &[192, 168, 1]),
+            (RecordType::Aaaa, &[0xfe, 0x80]),
+            (RecordType::Ptr, b"\x07printer\x05loc"),
+            (RecordType::Ptr, b"\x07printer\x05local"),
+            (RecordType::Srv, b"\x00\x01\x00\x02\x02"),
+            (RecordType::Txt, b"\x0cmodel"),
+        ];
+
+        for &(record_type, data) in truncated.iter() {
+            assert!(RecordData::try_decode(record_type, data).is_err());
+            assert_eq!(
+                RecordData::decode(record_type, data),
+                RecordData::Raw {
+                    record_type,
+                    data: data.to_vec(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn fails_to_decode_oversized_data() {
+        // Trailing bytes.
+        assert!(RecordData::try_decode(RecordType::A, &[192, 168, 1, 5, 0]).is_err());
+        assert!(RecordData::try_decode(RecordType::Ptr, b"\x05local\x00\x00").is_err());
+
+        // Label that is longer than 63 bytes (or compression pointer).
+        let mut label = vec![64];
+        label.extend_from_slice(&[b'a'; 64]);
+        label.push(0);
+        assert!(RecordData::try_decode(RecordType::Ptr, &label).is_err());
+        assert!(RecordData::try_decode(RecordType::Ptr, &[0xc0, 0x0c]).is_err());
+
+        // Name that is longer than 255 bytes.
+        let mut name = vec![];
+        for _ in 0..5 {
+            name.push(63);
+            name.extend_from_slice(&[b'a'; 63]);
+        }
+        name.push(0);
+        assert!(RecordData::try_decode(RecordType::Ptr, &name).is_err());
+
+        assert!(RecordData::try_decode(RecordType::Other(99), &[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn escapes_decoded_labels() {
+        let decoded = [
+            (&b"\x03a.b\x05local\x00"[..], "a\\.b.local"),
+            (&b"\x03a\\b\x00"[..], "a\\\\b"),
+            (&b"\x03a\x01b\x00"[..], "a\\001b"),
+            (&b"\x02\xffa\x00"[..], "\\255a"),
+            (&b"\x05B\xc3\xbcro\x00"[..], "B\u{fc}ro"),
+        ];
+
+        for &(data, name) in decoded.iter() {
+            let record = RecordData::Ptr(name.to_owned());
+
+            assert_eq!(
+                RecordData::try_decode(RecordType::Ptr, data).unwrap(),
+                record
+            );
+            assert_eq!(record.encode().unwrap(), data);
+        }
+    }
+}
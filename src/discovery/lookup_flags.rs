@@ -0,0 +1,38 @@
+/// Set of flags that control how services are looked up and resolved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LookupFlags(u32);
+
+impl LookupFlags {
+    /// Look up using wide area DNS only.
+    pub const USE_WIDE_AREA: LookupFlags = LookupFlags(1);
+    /// Look up using multicast DNS only.
+    pub const USE_MULTICAST: LookupFlags = LookupFlags(2);
+    /// Don't look up TXT record when resolving service.
+    pub const NO_TXT: LookupFlags = LookupFlags(4);
+    /// Don't look up address (A or AAAA) record when resolving service.
+    pub const NO_ADDRESS: LookupFlags = LookupFlags(8);
+}
+
+impl_flags!(LookupFlags);
+
+/// Set of flags that describe where discovered or resolved service comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LookupResultFlags(u32);
+
+impl LookupResultFlags {
+    /// The result has been found in the cache.
+    pub const CACHED: LookupResultFlags = LookupResultFlags(1);
+    /// The result has been found using wide area DNS.
+    pub const WIDE_AREA: LookupResultFlags = LookupResultFlags(2);
+    /// The result has been found using multicast DNS.
+    pub const MULTICAST: LookupResultFlags = LookupResultFlags(4);
+    /// The result has been published by the local host.
+    pub const LOCAL: LookupResultFlags = LookupResultFlags(8);
+    /// The result has been published through the same connection to the mDNS daemon
+    /// it has been found with.
+    pub const OUR_OWN: LookupResultFlags = LookupResultFlags(16);
+    /// The result comes from the static configuration of the local host.
+    pub const STATIC: LookupResultFlags = LookupResultFlags(32);
+}
+
+impl_flags!(LookupResultFlags);
@@ -1,3 +1,17 @@
+#[cfg(feature = "async")]
+pub use self::async_discovery::*;
 pub use self::discovery_manager::*;
+pub use self::discovery_options::DiscoveryOptions;
+pub use self::dns_record::*;
+pub use self::lookup_flags::{LookupFlags, LookupResultFlags};
+pub use self::service::{DiscoveredService, ResolvedService};
+pub use self::txt_record::*;
 
+#[cfg(feature = "async")]
+pub mod async_discovery;
 pub mod discovery_manager;
+pub mod discovery_options;
+pub mod dns_record;
+pub mod lookup_flags;
+pub mod service;
+pub mod txt_record;
@@ -0,0 +1,236 @@
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+
+use adapters::errors::Error;
+use discovery::discovery_manager::{ServiceInfo, ServiceProtocol};
+use discovery::lookup_flags::LookupResultFlags;
+use discovery::txt_record::TxtRecord;
+
+/// Service found by the service browser, it has to be resolved to get host, port and
+/// TXT record of the service.
+///
+/// Services are compared and hashed by name, type, domain, interface and protocol
+/// only, so the same service is the same map key regardless of lookup result flags.
+#[derive(Clone, Debug)]
+pub struct DiscoveredService {
+    pub name: String,
+    pub type_name: String,
+    pub domain: String,
+    /// Index of the network interface service has been found on.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+    pub flags: LookupResultFlags,
+}
+
+impl DiscoveredService {
+    /// Returns `true` if service is published by the local host.
+    pub fn is_local(&self) -> bool {
+        self.flags.contains(LookupResultFlags::LOCAL)
+    }
+
+    /// Returns `true` if service is published through the same connection to the mDNS
+    /// daemon it has been found with, see `ServiceInfo::is_our_own`.
+    pub fn is_our_own(&self) -> bool {
+        self.flags.contains(LookupResultFlags::OUR_OWN)
+    }
+}
+
+/// Service that has been resolved, contains everything needed to connect to it.
+///
+/// Just like `DiscoveredService`, it's compared and hashed by name, type, domain,
+/// interface and protocol only.
+#[derive(Clone, Debug)]
+pub struct ResolvedService {
+    pub name: String,
+    pub type_name: String,
+    pub domain: String,
+    /// Index of the network interface service has been found on.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+    pub flags: LookupResultFlags,
+    pub host_name: String,
+    /// Socket addresses of the service, may be empty if resolved with
+    /// `LookupFlags::NO_ADDRESS`.
+    pub addresses: Vec<SocketAddr>,
+    pub port: u16,
+    /// TXT record of the service, it's empty if resolved with `LookupFlags::NO_TXT`.
+    pub txt: TxtRecord,
+}
+
+impl ResolvedService {
+    pub fn is_local(&self) -> bool {
+        self.flags.contains(LookupResultFlags::LOCAL)
+    }
+
+    pub fn is_our_own(&self) -> bool {
+        self.flags.contains(LookupResultFlags::OUR_OWN)
+    }
+
+    /// Returns the part of the service that identifies it on the network.
+    pub fn discovered(&self) -> DiscoveredService {
+        DiscoveredService {
+            name: self.name.clone(),
+            type_name: self.type_name.clone(),
+            domain: self.domain.clone(),
+            interface: self.interface,
+            protocol: self.protocol,
+            flags: self.flags,
+        }
+    }
+}
+
+macro_rules! impl_service_identity {
+    ($name:ident) => {
+        impl $name {
+            fn identity(&self) -> (&str, &str, &str, i32, ServiceProtocol) {
+                (
+                    &self.name,
+                    &self.type_name,
+                    &self.domain,
+                    self.interface,
+                    self.protocol,
+                )
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.identity() == other.identity()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.identity().hash(state)
+            }
+        }
+    };
+}
+
+impl_service_identity!(DiscoveredService);
+impl_service_identity!(ResolvedService);
+
+/// Returns the service field, fails if it's missing.
+fn required_field(value: Option<String>, field: &str) -> Result<String, Error> {
+    value.ok_or_else(|| Error::Internal(format!("Service {} is missing", field)))
+}
+
+impl TryFrom<ServiceInfo> for DiscoveredService {
+    type Error = Error;
+
+    /// Fails if service name, type or domain is missing.
+    fn try_from(service: ServiceInfo) -> Result<Self, Error> {
+        Ok(DiscoveredService {
+            name: required_field(service.name, "name")?,
+            type_name: required_field(service.type_name, "type")?,
+            domain: required_field(service.domain, "domain")?,
+            interface: service.interface,
+            protocol: service.protocol,
+            flags: service.flags,
+        })
+    }
+}
+
+impl TryFrom<ServiceInfo> for ResolvedService {
+    type Error = Error;
+
+    /// Fails if service name, type, domain or host name is missing.
+    fn try_from(service: ServiceInfo) -> Result<Self, Error> {
+        let socket_addr = service.socket_addr();
+        let mut addresses = service.addresses;
+        if addresses.is_empty() {
+            addresses.extend(socket_addr);
+        }
+
+        Ok(ResolvedService {
+            name: required_field(service.name, "name")?,
+            type_name: required_field(service.type_name, "type")?,
+            domain: required_field(service.domain, "domain")?,
+            interface: service.interface,
+            protocol: service.protocol,
+            flags: service.flags,
+            host_name: required_field(service.host_name, "host name")?,
+            addresses,
+            port: service.port,
+            txt: service.txt.unwrap_or_default(),
+        })
+    }
+}
+
+impl From<DiscoveredService> for ServiceInfo {
+    fn from(service: DiscoveredService) -> Self {
+        ServiceInfo {
+            address: None,
+            addresses: Vec::new(),
+            domain: Some(service.domain),
+            host_name: None,
+            interface: service.interface,
+            name: Some(service.name),
+            port: 0,
+            protocol: service.protocol,
+            type_name: Some(service.type_name),
+            txt: None,
+            flags: service.flags,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn service_info() -> ServiceInfo {
+        ServiceInfo {
+            address: Some("192.168.1.2".parse().unwrap()),
+            addresses: Vec::new(),
+            domain: Some("local".to_owned()),
+            host_name: Some("printer.local".to_owned()),
+            interface: 2,
+            name: Some("Printer".to_owned()),
+            port: 631,
+            protocol: ServiceProtocol::IPv4,
+            type_name: Some("_ipp._tcp".to_owned()),
+            txt: None,
+            flags: LookupResultFlags::MULTICAST,
+        }
+    }
+
+    #[test]
+    fn identifies_services_regardless_of_flags() {
+        let service = DiscoveredService::try_from(service_info()).unwrap();
+        let mut cached = service.clone();
+        cached.flags = LookupResultFlags::CACHED;
+
+        let mut services = HashSet::new();
+        services.insert(service.clone());
+        assert!(services.contains(&cached));
+
+        let mut other = service.clone();
+        other.interface = 3;
+        assert_ne!(service, other);
+    }
+
+    #[test]
+    fn converts_complete_services_only() {
+        let service = ResolvedService::try_from(service_info()).unwrap();
+        assert_eq!(service.addresses, vec!["192.168.1.2:631".parse().unwrap()]);
+        assert_eq!(service.txt, TxtRecord::new());
+
+        let mut info = service_info();
+        info.host_name = None;
+        assert!(DiscoveredService::try_from(info).is_ok());
+
+        let mut info = service_info();
+        info.host_name = None;
+        assert!(ResolvedService::try_from(info).is_err());
+
+        let mut info = service_info();
+        info.domain = None;
+        assert!(DiscoveredService::try_from(info).is_err());
+    }
+}
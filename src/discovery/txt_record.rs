@@ -0,0 +1,257 @@
+use std::fmt;
+use std::slice;
+
+use adapters::errors::Error;
+
+/// Single `key[=value]` entry of a DNS-SD TXT record (see RFC 6763, section 6.3).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TxtEntry {
+    pub key: String,
+    /// Raw value of the entry, `None` for boolean attributes that don't have `=`.
+    pub value: Option<Vec<u8>>,
+}
+
+impl TxtEntry {
+    /// Parses raw TXT string into the entry. Returns `None` for strings that
+    /// don't contain a key (empty strings or strings starting with `=`), such
+    /// strings must be silently ignored according to RFC 6763, section 6.4.
+    pub fn from_bytes(bytes: &[u8]) -> Option<TxtEntry> {
+        let (key, value) = match bytes.iter().position(|byte| *byte == b'=') {
+            Some(position) => (&bytes[..position], Some(bytes[position + 1..].to_vec())),
+            None => (bytes, None),
+        };
+
+        if key.is_empty() {
+            return None;
+        }
+
+        Some(TxtEntry {
+            key: String::from_utf8_lossy(key).into_owned(),
+            value,
+        })
+    }
+
+    /// Serializes entry into the raw `key[=value]` TXT string.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.key.as_bytes().to_vec();
+
+        if let Some(ref value) = self.value {
+            bytes.push(b'=');
+            bytes.extend_from_slice(value);
+        }
+
+        bytes
+    }
+}
+
+/// Ordered collection of TXT record entries. Keys are compared case-insensitively
+/// as required by RFC 6763, section 6.4.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TxtRecord {
+    entries: Vec<TxtEntry>,
+}
+
+impl TxtRecord {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Checks whether `key` can be used as a TXT record key: it must be at least one
+    /// character long and consist of printable US-ASCII characters except `=`.
+    pub fn is_valid_key(key: &str) -> bool {
+        !key.is_empty()
+            && key
+                .bytes()
+                .all(|byte| (0x20..=0x7E).contains(&byte) && byte != b'=')
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, TxtEntry> {
+        self.entries.iter()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Returns value for the specified key. `None` is returned both when key is not
+    /// present and when it's a boolean attribute, use `contains_key` to distinguish.
+    pub fn get(&self, key: &str) -> Option<&[u8]> {
+        self.position(key)
+            .and_then(|position| self.entries[position].value.as_ref())
+            .map(|value| &value[..])
+    }
+
+    /// Same as `get`, but returns `None` for values that are not valid UTF-8 too.
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key)
+            .and_then(|value| ::std::str::from_utf8(value).ok())
+    }
+
+    /// Inserts `key=value` entry, replacing value of the existing entry with the same
+    /// key if there is one. Fails if key isn't valid, see `is_valid_key`.
+    pub fn insert<V: AsRef<[u8]>>(&mut self, key: &str, value: V) -> Result<(), Error> {
+        self.set(key, Some(value.as_ref().to_vec()))
+    }
+
+    /// Inserts boolean attribute (key without `=`), replacing the existing entry with
+    /// the same key if there is one. Fails if key isn't valid, see `is_valid_key`.
+    pub fn insert_flag(&mut self, key: &str) -> Result<(), Error> {
+        self.set(key, None)
+    }
+
+    /// Appends entry to the end of the record unless entry with the same key is
+    /// already present. Returns `false` if entry has been ignored, this mirrors
+    /// RFC 6763 requirement to ignore all but the first occurrence of the key. Key
+    /// isn't validated, so that entries received from the network are kept as is.
+    pub fn push(&mut self, entry: TxtEntry) -> bool {
+        if self.contains_key(&entry.key) {
+            return false;
+        }
+
+        self.entries.push(entry);
+        true
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<TxtEntry> {
+        self.position(key)
+            .map(|position| self.entries.remove(position))
+    }
+
+    fn set(&mut self, key: &str, value: Option<Vec<u8>>) -> Result<(), Error> {
+        if !TxtRecord::is_valid_key(key) {
+            return Err(Error::Internal(format!(
+                "{:?} is not a valid TXT record key",
+                key
+            )));
+        }
+
+        match self.position(key) {
+            Some(position) => self.entries[position].value = value,
+            None => self.entries.push(TxtEntry {
+                key: key.to_owned(),
+                value,
+            }),
+        }
+
+        Ok(())
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| entry.key.eq_ignore_ascii_case(key))
+    }
+}
+
+impl<'a> IntoIterator for &'a TxtRecord {
+    type Item = &'a TxtEntry;
+    type IntoIter = slice::Iter<'a, TxtEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Formats record the same way `avahi_string_list_to_string` does: every entry is
+/// enclosed in "" and entries are separated by spaces.
+impl fmt::Display for TxtRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, entry) in self.entries.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+
+            write!(f, "\"{}\"", String::from_utf8_lossy(&entry.to_bytes()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_keys_up_case_insensitively() {
+        let mut txt = TxtRecord::new();
+        txt.insert("Model", "Xserve").unwrap();
+        txt.insert("MODEL", "Xserve 2").unwrap();
+
+        assert_eq!(txt.len(), 1);
+        assert!(txt.contains_key("model"));
+        assert_eq!(txt.get_str("mOdEl"), Some("Xserve 2"));
+        assert_eq!(txt.remove("model").unwrap().key, "Model");
+        assert!(txt.is_empty());
+    }
+
+    #[test]
+    fn distinguishes_flags_empty_and_binary_values() {
+        let mut txt = TxtRecord::new();
+        txt.insert_flag("color").unwrap();
+        txt.insert("paper", "").unwrap();
+        txt.insert("id", [0xff, 0x00]).unwrap();
+
+        assert!(txt.contains_key("color"));
+        assert_eq!(txt.get("color"), None);
+        assert_eq!(txt.get("paper"), Some(&b""[..]));
+        assert_eq!(txt.get("id"), Some(&[0xff, 0x00][..]));
+        assert_eq!(txt.get_str("id"), None);
+        assert!(!txt.contains_key("duplex"));
+
+        let entries: Vec<_> = txt.iter().map(TxtEntry::to_bytes).collect();
+        assert_eq!(
+            entries,
+            vec![
+                b"color".to_vec(),
+                b"paper=".to_vec(),
+                b"id=\xff\x00".to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let mut txt = TxtRecord::new();
+
+        assert!(txt.insert("", "value").is_err());
+        assert!(txt.insert("a=b", "value").is_err());
+        assert!(txt.insert_flag("caf\u{e9}").is_err());
+        assert!(txt.insert_flag("new\nline").is_err());
+        assert!(txt.is_empty());
+    }
+
+    #[test]
+    fn keeps_first_occurrence_of_duplicate_keys() {
+        let mut txt = TxtRecord::new();
+
+        for bytes in [&b"path=/api"[..], b"PATH=/other", b"=ignored", b""].iter() {
+            if let Some(entry) = TxtEntry::from_bytes(bytes) {
+                txt.push(entry);
+            }
+        }
+
+        assert_eq!(txt.len(), 1);
+        assert_eq!(txt.get_str("path"), Some("/api"));
+    }
+
+    #[test]
+    fn formats_entries_like_avahi() {
+        let mut txt = TxtRecord::new();
+        assert_eq!(txt.to_string(), "");
+
+        txt.insert("model", "Xserve").unwrap();
+        txt.insert_flag("color").unwrap();
+        txt.insert("path", "/api").unwrap();
+
+        assert_eq!(txt.to_string(), "\"model=Xserve\" \"color\" \"path=/api\"");
+    }
+}
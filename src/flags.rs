@@ -0,0 +1,45 @@
+/// Implements methods and operators shared by the flag sets, `$name` has to be a tuple
+/// struct that wraps `u32`.
+macro_rules! impl_flags {
+    ($name:ident) => {
+        impl $name {
+            pub fn empty() -> Self {
+                $name(0)
+            }
+
+            pub fn bits(self) -> u32 {
+                self.0
+            }
+
+            pub fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            pub fn contains(self, other: $name) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub fn insert(&mut self, other: $name) {
+                self.0 |= other.0;
+            }
+
+            pub fn remove(&mut self, other: $name) {
+                self.0 &= !other.0;
+            }
+        }
+
+        impl ::std::ops::BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, other: $name) -> $name {
+                $name(self.0 | other.0)
+            }
+        }
+
+        impl ::std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, other: $name) {
+                self.insert(other);
+            }
+        }
+    };
+}
@@ -0,0 +1,66 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use adapters::errors::Error;
+use discovery::discovery_manager::ServiceProtocol;
+use discovery::discovery_options::interface_index;
+use host::publish_flags::PublishFlags;
+use host::service_registration::DEFAULT_REGISTRATION_TIMEOUT;
+
+/// Describes address record (A or AAAA) that should be published for the host that
+/// can't announce itself. Use `new` to create registration with the mandatory fields
+/// and builder methods to set the rest.
+#[derive(Clone, Debug)]
+pub struct AddressRegistration {
+    /// Host name address is published for, it's changed on collisions.
+    pub name: String,
+    pub address: IpAddr,
+    /// Index of the network interface to publish address on, `-1` means all interfaces.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+    pub flags: PublishFlags,
+    /// Time to wait for address to be established, address that isn't established in
+    /// time is withdrawn.
+    pub timeout: Duration,
+}
+
+impl AddressRegistration {
+    pub fn new(name: &str, address: IpAddr) -> Self {
+        AddressRegistration {
+            name: name.to_owned(),
+            address,
+            interface: -1,
+            protocol: ServiceProtocol::Unspecified,
+            flags: PublishFlags::default(),
+            timeout: DEFAULT_REGISTRATION_TIMEOUT,
+        }
+    }
+
+    pub fn interface(mut self, interface: i32) -> Self {
+        self.interface = interface;
+        self
+    }
+
+    /// Same as `interface`, but interface is specified by name (e.g. `br0`), fails if
+    /// there is no such interface.
+    pub fn interface_name(self, name: &str) -> Result<Self, Error> {
+        Ok(self.interface(interface_index(name)?))
+    }
+
+    /// Restricts publishing to either IPv4 or IPv6 network.
+    pub fn protocol(mut self, protocol: ServiceProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Sets publish flags, e.g. `PublishFlags::NO_REVERSE` to skip reverse PTR record.
+    pub fn flags(mut self, flags: PublishFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
@@ -0,0 +1,233 @@
+use std::pin::Pin;
+use std::sync::mpsc::TryRecvError;
+use std::task::{Context, Poll};
+
+use futures::channel::mpsc;
+use futures::{Future, StreamExt};
+
+use adapters::errors::Error;
+use host::address_registration::AddressRegistration;
+use host::host_manager::{
+    AnnouncedService, HostManager, PublishEvent, PublishedRecord, ServiceEvent,
+};
+use host::record_registration::RecordRegistration;
+use host::service_registration::ServiceRegistration;
+
+/// Future that completes once service is established on the network, service is
+/// withdrawn as soon as the future is dropped before that.
+///
+/// Future shares the adapter with the manager it has been created by, so it's `Send`,
+/// can outlive the manager and be passed to `tokio::spawn`.
+#[must_use = "futures do nothing unless polled"]
+pub struct RegisterServiceFuture {
+    service: Option<AnnouncedService>,
+    /// Signals that new service event has been queued.
+    signal: mpsc::UnboundedReceiver<()>,
+}
+
+impl Future for RegisterServiceFuture {
+    type Output = Result<AnnouncedService, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let result = match self.service {
+                Some(ref service) => match service.events.try_recv() {
+                    Ok(ServiceEvent::Established(_)) => Some(Ok(())),
+                    Ok(ServiceEvent::Collision { .. }) => continue,
+                    Ok(ServiceEvent::Failed(error)) => Some(Err(error)),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => Some(Err(Error::Internal(
+                        "Service registration has been interrupted".to_owned(),
+                    ))),
+                },
+                None => panic!("RegisterServiceFuture polled after completion"),
+            };
+
+            if let Some(result) = result {
+                let service = self.service.take().unwrap();
+                return Poll::Ready(result.map(|_| service));
+            }
+
+            // Events are queued before the signal is sent, so there is something to
+            // receive every time signal arrives.
+            match self.signal.poll_next_unpin(cx) {
+                Poll::Ready(Some(())) => continue,
+                Poll::Ready(None) => {
+                    return Poll::Ready(Err(Error::Internal(
+                        "Service registration has been interrupted".to_owned(),
+                    )))
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Future that completes once DNS records are established on the network, records are
+/// withdrawn as soon as the future is dropped before that. Just like
+/// `RegisterServiceFuture`, it's `Send`.
+#[must_use = "futures do nothing unless polled"]
+pub struct PublishRecordFuture {
+    record: Option<PublishedRecord>,
+    /// Signals that new publish event has been queued.
+    signal: mpsc::UnboundedReceiver<()>,
+}
+
+impl Future for PublishRecordFuture {
+    type Output = Result<PublishedRecord, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            let result = match self.record {
+                Some(ref record) => match record.events.try_recv() {
+                    Ok(PublishEvent::Established(_)) => Some(Ok(())),
+                    Ok(PublishEvent::Collision { .. }) => continue,
+                    Ok(PublishEvent::Failed(error)) => Some(Err(error)),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => Some(Err(Error::Internal(
+                        "Record publishing has been interrupted".to_owned(),
+                    ))),
+                },
+                None => panic!("PublishRecordFuture polled after completion"),
+            };
+
+            if let Some(result) = result {
+                let record = self.record.take().unwrap();
+                return Poll::Ready(result.map(|_| record));
+            }
+
+            // Events are queued before the signal is sent, so there is something to
+            // receive every time signal arrives.
+            match self.signal.poll_next_unpin(cx) {
+                Poll::Ready(Some(())) => continue,
+                Poll::Ready(None) => {
+                    return Poll::Ready(Err(Error::Internal(
+                        "Record publishing has been interrupted".to_owned(),
+                    )))
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl HostManager {
+    /// Same as `announce_service`, but doesn't block until service is established.
+    pub fn announce_service_async(
+        &self,
+        name: &str,
+        service_type: &str,
+        port: u16,
+    ) -> Result<RegisterServiceFuture, Error> {
+        self.register_service_async(ServiceRegistration::new(name, service_type, port))
+    }
+
+    /// Same as `register_service`, but doesn't block until service is established.
+    pub fn register_service_async(
+        &self,
+        registration: ServiceRegistration,
+    ) -> Result<RegisterServiceFuture, Error> {
+        let (sender, signal) = mpsc::unbounded();
+
+        let service = self.start_service_registration(
+            &registration,
+            Box::new(move || {
+                let _ = sender.unbounded_send(());
+            }),
+        )?;
+
+        Ok(RegisterServiceFuture {
+            service: Some(service),
+            signal,
+        })
+    }
+
+    /// Same as `publish_record`, but doesn't block until record is established.
+    pub fn publish_record_async(
+        &self,
+        registration: RecordRegistration,
+    ) -> Result<PublishRecordFuture, Error> {
+        let (sender, signal) = mpsc::unbounded();
+
+        let record = self.start_record_publishing(
+            &registration,
+            Box::new(move || {
+                let _ = sender.unbounded_send(());
+            }),
+        )?;
+
+        Ok(PublishRecordFuture {
+            record: Some(record),
+            signal,
+        })
+    }
+
+    /// Same as `register_address`, but doesn't block until records are established.
+    pub fn register_address_async(
+        &self,
+        registration: AddressRegistration,
+    ) -> Result<PublishRecordFuture, Error> {
+        let (sender, signal) = mpsc::unbounded();
+
+        let record = self.start_address_publishing(
+            &registration,
+            Box::new(move || {
+                let _ = sender.unbounded_send(());
+            }),
+        )?;
+
+        Ok(PublishRecordFuture {
+            record: Some(record),
+            signal,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::thread;
+
+    use futures::executor::block_on;
+
+    use super::*;
+    use adapters::fake::FakeAdapter;
+    use discovery::dns_record::RecordData;
+    use discovery::txt_record::TxtRecord;
+
+    #[test]
+    fn completes_once_service_is_established() {
+        let manager = HostManager::with_fake_adapter(FakeAdapter::default());
+        let registration = ServiceRegistration::new("Printer", "_ipp._tcp", 631);
+
+        let service = block_on(manager.register_service_async(registration).unwrap()).unwrap();
+
+        assert_eq!(service.name().unwrap(), "Printer");
+    }
+
+    #[test]
+    fn completes_on_another_thread() {
+        let manager = HostManager::with_fake_adapter(FakeAdapter::default());
+        let registration = ServiceRegistration::new("Printer", "_ipp._tcp", 631);
+        let future = manager.register_service_async(registration).unwrap();
+
+        let service = thread::spawn(move || block_on(future)).join().unwrap();
+
+        assert_eq!(service.unwrap().name().unwrap(), "Printer");
+    }
+
+    #[test]
+    fn completes_once_records_are_established() {
+        let manager = HostManager::with_fake_adapter(FakeAdapter::default());
+        let address = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+
+        let registration = AddressRegistration::new("camera.local", address);
+        let record = block_on(manager.register_address_async(registration).unwrap()).unwrap();
+        assert_eq!(record.name().unwrap(), "camera.local");
+
+        let registration =
+            RecordRegistration::new("_camera._tcp.local", RecordData::Txt(TxtRecord::new()));
+        let record = block_on(manager.publish_record_async(registration).unwrap()).unwrap();
+        assert_eq!(record.name().unwrap(), "_camera._tcp.local");
+    }
+}
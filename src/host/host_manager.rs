@@ -1,10 +1,133 @@
+use std::net::IpAddr;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
 use adapters::adapter::Adapter;
-use adapters::adapter::HostAdapter;
+use adapters::adapter::{DiscoveryAdapter, HostAdapter};
 use adapters::errors::Error;
+#[cfg(any(test, feature = "test-util"))]
+use adapters::fake::FakeAdapter;
 use adapters::PlatformDependentAdapter;
+use discovery::discovery_manager::DiscoveryManager;
+use discovery::txt_record::TxtRecord;
+use host::address_registration::AddressRegistration;
+use host::name_alias::{AliasState, NameAlias};
+use host::publish_flags::PublishFlags;
+use host::record_registration::RecordRegistration;
+use host::service_registration::ServiceRegistration;
 
 pub struct HostManager {
-    adapter: Box<dyn HostAdapter>,
+    pub(crate) adapter: Arc<dyn HostAdapter>,
+    /// The same adapter as `adapter`, kept to create discovery managers that share it.
+    discovery_adapter: Arc<dyn DiscoveryAdapter>,
+}
+
+/// Events that happen to the announced service after it has been registered.
+#[derive(Debug)]
+pub enum ServiceEvent {
+    /// Service has been successfully announced under the specified name.
+    Established(String),
+    /// Service name collided with another service on the network, so service has
+    /// been automatically re-announced under the new name.
+    Collision { name: String, new_name: String },
+    /// Service couldn't be announced and is no longer published.
+    Failed(Error),
+}
+
+/// Handle to the service announced with `HostManager`. Service stays announced as
+/// long as the handle is alive and is withdrawn as soon as the handle is dropped.
+#[must_use = "service is withdrawn as soon as the handle is dropped"]
+pub struct AnnouncedService {
+    adapter: Arc<dyn HostAdapter>,
+    id: usize,
+    pub(crate) events: mpsc::Receiver<ServiceEvent>,
+}
+
+impl AnnouncedService {
+    /// Returns the name service is currently announced under, it may differ from
+    /// the requested one if there were name collisions.
+    pub fn name(&self) -> Result<String, Error> {
+        self.adapter.get_service_name(self.id)
+    }
+
+    /// Returns events that happened to the service since the last call, e.g.
+    /// collisions that caused service to be renamed.
+    pub fn pending_events(&self) -> Vec<ServiceEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// Replaces TXT record of the announced service without re-announcing it. If
+    /// service is announced on several interfaces and update fails on one of them,
+    /// interfaces updated before it keep the new record, error lists them.
+    pub fn update_txt(&self, txt: TxtRecord) -> Result<(), Error> {
+        self.adapter.update_service_txt(self.id, &txt)
+    }
+
+    /// Withdraws service from the network, but keeps it registered so that it can be
+    /// announced again with `republish`.
+    pub fn reset(&self) -> Result<(), Error> {
+        self.adapter.reset_service(self.id)
+    }
+
+    /// Announces service again after it has been withdrawn with `reset`.
+    pub fn republish(&self) -> Result<(), Error> {
+        self.adapter.republish_service(self.id)
+    }
+
+    /// Withdraws service from the network and releases all associated resources,
+    /// it's the same as dropping the handle.
+    pub fn withdraw(self) {}
+}
+
+impl Drop for AnnouncedService {
+    fn drop(&mut self) {
+        self.adapter.unregister_service(self.id);
+    }
+}
+
+/// Events that happen to the published DNS records after they have been registered.
+#[derive(Debug)]
+pub enum PublishEvent {
+    /// Records have been successfully published under the specified name.
+    Established(String),
+    /// Host name collided with another host on the network, so address records have
+    /// been automatically re-published under the new name.
+    Collision { name: String, new_name: String },
+    /// Records couldn't be published (e.g. because of name collision) and are no
+    /// longer published.
+    Failed(Error),
+}
+
+/// Handle to the DNS records published with `HostManager`. Records stay published as
+/// long as the handle is alive and are withdrawn as soon as the handle is dropped.
+#[must_use = "records are withdrawn as soon as the handle is dropped"]
+pub struct PublishedRecord {
+    adapter: Arc<dyn HostAdapter>,
+    id: usize,
+    pub(crate) events: mpsc::Receiver<PublishEvent>,
+}
+
+impl PublishedRecord {
+    /// Returns the name records are currently published under, it may differ from the
+    /// requested one if there were host name collisions.
+    pub fn name(&self) -> Result<String, Error> {
+        self.adapter.get_record_name(self.id)
+    }
+
+    /// Returns events that happened to the records since the last call, e.g.
+    /// collisions that caused address records to be renamed.
+    pub fn pending_events(&self) -> Vec<PublishEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// Withdraws records from the network, it's the same as dropping the handle.
+    pub fn withdraw(self) {}
+}
+
+impl Drop for PublishedRecord {
+    fn drop(&mut self) {
+        self.adapter.unpublish_record(self.id);
+    }
 }
 
 impl HostManager {
@@ -12,6 +135,28 @@ impl HostManager {
         Default::default()
     }
 
+    /// Creates manager that uses `FakeAdapter` instead of the platform adapter, so that
+    /// code can be exercised without mDNS daemon.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn with_fake_adapter(adapter: FakeAdapter) -> Self {
+        let adapter = Arc::new(adapter);
+
+        HostManager {
+            adapter: adapter.clone(),
+            discovery_adapter: adapter,
+        }
+    }
+
+    /// Returns discovery manager that shares the connection to the mDNS daemon with
+    /// this manager, so that services registered here are reported with
+    /// `LookupResultFlags::OUR_OWN` flag and can be left out with
+    /// `DiscoveryOptions::skip_our_own`.
+    pub fn discovery_manager(&self) -> DiscoveryManager {
+        DiscoveryManager {
+            adapter: self.discovery_adapter.clone(),
+        }
+    }
+
     pub fn get_name(&self) -> Result<String, Error> {
         self.adapter.get_name()
     }
@@ -28,19 +173,324 @@ impl HostManager {
         self.adapter.get_alternative_name(name)
     }
 
+    pub fn get_alternative_service_name(&self, name: &str) -> Result<String, Error> {
+        self.adapter.get_alternative_service_name(name)
+    }
+
+    /// Adds CNAME alias for the local host with the default TTL, it's the same as
+    /// `register_name_alias` with `NameAlias::new(name)`.
     pub fn add_name_alias(&self, name: &str) -> Result<(), Error> {
-        self.adapter.add_name_alias(name)
+        self.register_name_alias(NameAlias::new(name))
+    }
+
+    /// Publishes additional name for the local host, alias with the same name is
+    /// replaced. Blocks until alias is established, alias that can't be published
+    /// (e.g. because of name collision) or isn't established within `alias.timeout` is
+    /// removed and error is returned.
+    pub fn register_name_alias(&self, alias: NameAlias) -> Result<(), Error> {
+        let (sender, events) = mpsc::channel();
+
+        self.adapter.add_name_alias(
+            &alias,
+            Box::new(move |event| {
+                // Receiver is gone once alias has been established or failed.
+                let _ = sender.send(event);
+            }),
+        )?;
+
+        let error = match events.recv_timeout(alias.timeout) {
+            Ok(PublishEvent::Established(_)) => return Ok(()),
+            Ok(PublishEvent::Failed(error)) => error,
+            Ok(PublishEvent::Collision { name, .. }) => {
+                Error::Internal(format!("Alias {:?} collided with another host", name))
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                Error::Timeout("Alias registration has timed out".to_owned())
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Error::Internal("Alias registration has been interrupted".to_owned())
+            }
+        };
+
+        let _ = self.adapter.remove_name_alias(&alias.name);
+
+        Err(error)
+    }
+
+    /// Returns all registered host name aliases along with their current state, so
+    /// that collisions that happened after alias had been established can be noticed.
+    pub fn name_aliases(&self) -> Vec<(NameAlias, AliasState)> {
+        self.adapter.get_name_aliases()
     }
 
-    pub fn announce_service(&self, name: &str, service_type: &str, port: u16) -> Result<(), Error> {
-        self.adapter.announce_service(name, service_type, port)
+    /// Withdraws host name alias with the specified name.
+    pub fn remove_name_alias(&self, name: &str) -> Result<(), Error> {
+        self.adapter.remove_name_alias(name)
+    }
+
+    pub fn announce_service(
+        &self,
+        name: &str,
+        service_type: &str,
+        port: u16,
+    ) -> Result<AnnouncedService, Error> {
+        self.register_service(ServiceRegistration::new(name, service_type, port))
+    }
+
+    /// Registers service without waiting for it to be established, `notify` is called
+    /// every time new event is queued for the returned handle.
+    pub(crate) fn start_service_registration(
+        &self,
+        registration: &ServiceRegistration,
+        notify: Box<dyn Fn() + Send>,
+    ) -> Result<AnnouncedService, Error> {
+        let (sender, events) = mpsc::channel();
+
+        let id = self.adapter.register_service(
+            registration,
+            Box::new(move |event| {
+                // Receiver is gone only if service has been withdrawn.
+                let _ = sender.send(event);
+                notify();
+            }),
+        )?;
+
+        Ok(AnnouncedService {
+            adapter: self.adapter.clone(),
+            id,
+            events,
+        })
+    }
+
+    pub fn register_service(
+        &self,
+        registration: ServiceRegistration,
+    ) -> Result<AnnouncedService, Error> {
+        let service = self.start_service_registration(&registration, Box::new(|| {}))?;
+        let deadline = Instant::now() + registration.timeout;
+
+        // Registration blocks until service is either established, failed or timed out,
+        // name collisions are resolved automatically by picking alternative names.
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match service.events.recv_timeout(timeout) {
+                Ok(ServiceEvent::Established(_)) => return Ok(service),
+                Ok(ServiceEvent::Collision { .. }) => continue,
+                Ok(ServiceEvent::Failed(error)) => return Err(error),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    return Err(Error::Timeout(
+                        "Service registration has timed out".to_owned(),
+                    ))
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(Error::Internal(
+                        "Service registration has been interrupted".to_owned(),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Publishes DNS record described by `registration`, blocks until record is either
+    /// established, failed or timed out. Record data is validated and encoded before
+    /// publishing.
+    pub fn publish_record(
+        &self,
+        registration: RecordRegistration,
+    ) -> Result<PublishedRecord, Error> {
+        let record = self.start_record_publishing(&registration, Box::new(|| {}))?;
+
+        self.wait_for_record(record, registration.timeout)
+    }
+
+    /// Publishes DNS record without waiting for it to be established, `notify` is called
+    /// every time new event is queued for the returned handle.
+    pub(crate) fn start_record_publishing(
+        &self,
+        registration: &RecordRegistration,
+        notify: Box<dyn Fn() + Send>,
+    ) -> Result<PublishedRecord, Error> {
+        let (sender, events) = mpsc::channel();
+
+        let id = self.adapter.publish_record(
+            registration,
+            Box::new(move |event| {
+                // Receiver is gone only if record has been withdrawn.
+                let _ = sender.send(event);
+                notify();
+            }),
+        )?;
+
+        Ok(PublishedRecord {
+            adapter: self.adapter.clone(),
+            id,
+            events,
+        })
+    }
+
+    /// Publishes address record (A or AAAA) for the host that can't announce itself,
+    /// along with the reverse PTR record. It's the same as `publish_address_with_flags`
+    /// with the default flags.
+    pub fn publish_address(&self, name: &str, address: IpAddr) -> Result<PublishedRecord, Error> {
+        self.publish_address_with_flags(name, address, PublishFlags::default())
+    }
+
+    /// Publishes address record for the host with the specified name, reverse PTR
+    /// record is skipped if `flags` contain `PublishFlags::NO_REVERSE`. It's the same as
+    /// `register_address` with the default interface, protocol and timeout.
+    pub fn publish_address_with_flags(
+        &self,
+        name: &str,
+        address: IpAddr,
+        flags: PublishFlags,
+    ) -> Result<PublishedRecord, Error> {
+        self.register_address(AddressRegistration::new(name, address).flags(flags))
+    }
+
+    /// Publishes address record described by `registration`. Blocks until records are
+    /// established, failed or timed out, name collisions are resolved automatically by
+    /// picking alternative host names.
+    pub fn register_address(
+        &self,
+        registration: AddressRegistration,
+    ) -> Result<PublishedRecord, Error> {
+        let record = self.start_address_publishing(&registration, Box::new(|| {}))?;
+
+        self.wait_for_record(record, registration.timeout)
+    }
+
+    /// Publishes address record without waiting for it to be established, `notify` is
+    /// called every time new event is queued for the returned handle.
+    pub(crate) fn start_address_publishing(
+        &self,
+        registration: &AddressRegistration,
+        notify: Box<dyn Fn() + Send>,
+    ) -> Result<PublishedRecord, Error> {
+        let (sender, events) = mpsc::channel();
+
+        let id = self.adapter.publish_address(
+            registration,
+            Box::new(move |event| {
+                // Receiver is gone only if address has been withdrawn.
+                let _ = sender.send(event);
+                notify();
+            }),
+        )?;
+
+        Ok(PublishedRecord {
+            adapter: self.adapter.clone(),
+            id,
+            events,
+        })
+    }
+
+    /// Waits for records to be established, records are withdrawn if they fail or
+    /// aren't established within `timeout`.
+    fn wait_for_record(
+        &self,
+        record: PublishedRecord,
+        timeout: Duration,
+    ) -> Result<PublishedRecord, Error> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match record.events.recv_timeout(timeout) {
+                Ok(PublishEvent::Established(_)) => return Ok(record),
+                Ok(PublishEvent::Collision { .. }) => continue,
+                Ok(PublishEvent::Failed(error)) => return Err(error),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    return Err(Error::Timeout("Record publishing has timed out".to_owned()))
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(Error::Internal(
+                        "Record publishing has been interrupted".to_owned(),
+                    ))
+                }
+            }
+        }
     }
 }
 
 impl Default for HostManager {
     fn default() -> Self {
-        let adapter: Box<dyn HostAdapter> = Box::new(PlatformDependentAdapter::new());
+        let adapter = Arc::new(PlatformDependentAdapter::new());
+
+        HostManager {
+            adapter: adapter.clone(),
+            discovery_adapter: adapter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use discovery::dns_record::RecordData;
+    use host::name_alias::AliasKind;
+
+    #[test]
+    fn publishes_records_with_valid_data_only() {
+        let manager = HostManager::with_fake_adapter(FakeAdapter::default());
+
+        let address = RecordData::A(Ipv4Addr::new(192, 168, 1, 5));
+        let record = manager
+            .publish_record(RecordRegistration::new("printer.local", address))
+            .unwrap();
+        assert_eq!(record.name().unwrap(), "printer.local");
+        assert!(record.pending_events().is_empty());
+
+        let target = RecordData::Ptr("a".repeat(64));
+        let result = manager.publish_record(RecordRegistration::new("printer.local", target));
+        assert!(matches!(result, Err(Error::Internal(_))));
+    }
+
+    #[test]
+    fn records_registered_services() {
+        let fake = FakeAdapter::default();
+        let manager = HostManager::with_fake_adapter(fake.clone());
+
+        let registration = ServiceRegistration::new("Printer", "_http._tcp", 631)
+            .subtype("_printer")
+            .host("printer.local")
+            .txt_entry("path", "/ipp")
+            .unwrap();
+        let service = manager.register_service(registration).unwrap();
+
+        let registered = fake.registered_services();
+        assert_eq!(registered.len(), 1);
+        assert_eq!(registered[0].name, "Printer");
+        assert_eq!(
+            registered[0].full_subtypes(),
+            vec!["_printer._sub._http._tcp".to_owned()]
+        );
+        assert_eq!(registered[0].host, Some("printer.local".to_owned()));
+        assert_eq!(registered[0].txt.get_str("path"), Some("/ipp"));
+
+        service.withdraw();
+        assert!(fake.registered_services().is_empty());
+    }
+
+    #[test]
+    fn replaces_and_removes_name_aliases() {
+        let manager = HostManager::with_fake_adapter(FakeAdapter::default());
+        let address = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 5));
+
+        manager.add_name_alias("printer.local").unwrap();
+        manager
+            .register_name_alias(NameAlias::new("printer.local").address(address).ttl(300))
+            .unwrap();
+
+        let aliases = manager.name_aliases();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].0.kind, AliasKind::Address(address));
+        assert_eq!(aliases[0].0.ttl, 300);
+        assert_eq!(aliases[0].1, AliasState::Established);
 
-        HostManager { adapter }
+        manager.remove_name_alias("printer.local").unwrap();
+        assert!(manager.name_aliases().is_empty());
+        assert!(manager.remove_name_alias("printer.local").is_err());
     }
 }
@@ -1,3 +1,19 @@
-pub use self::host_manager::HostManager;
+pub use self::address_registration::AddressRegistration;
+#[cfg(feature = "async")]
+pub use self::async_host::{PublishRecordFuture, RegisterServiceFuture};
+pub use self::host_manager::{
+    AnnouncedService, HostManager, PublishEvent, PublishedRecord, ServiceEvent,
+};
+pub use self::name_alias::{AliasKind, AliasState, NameAlias};
+pub use self::publish_flags::PublishFlags;
+pub use self::record_registration::RecordRegistration;
+pub use self::service_registration::ServiceRegistration;
 
+pub mod address_registration;
+#[cfg(feature = "async")]
+pub mod async_host;
 pub mod host_manager;
+pub mod name_alias;
+pub mod publish_flags;
+pub mod record_registration;
+pub mod service_registration;
@@ -0,0 +1,68 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use host::service_registration::DEFAULT_REGISTRATION_TIMEOUT;
+
+/// TTL host name aliases are published with by default.
+pub const DEFAULT_ALIAS_TTL: u32 = 60;
+
+/// Kind of the record host name alias is published with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AliasKind {
+    /// CNAME record that points to the fully qualified name of the local host.
+    Cname,
+    /// Address record (A or AAAA, depending on the address family) with the address.
+    Address(IpAddr),
+}
+
+/// State of the host name alias registered with `HostManager`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AliasState {
+    /// Alias is being probed and announced.
+    Registering,
+    Established,
+    /// Alias name collided with the record of another host and is no longer published.
+    Collision,
+    /// Alias couldn't be published and is no longer published.
+    Failed,
+}
+
+/// Describes additional name the local host should be reachable by. Use `new` to create
+/// CNAME alias with the default TTL and builder methods to change that.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NameAlias {
+    pub name: String,
+    pub kind: AliasKind,
+    /// Time in seconds other hosts are allowed to cache the alias record for.
+    pub ttl: u32,
+    /// Time `HostManager::register_name_alias` waits for alias to be established,
+    /// alias that isn't established in time is removed.
+    pub timeout: Duration,
+}
+
+impl NameAlias {
+    pub fn new(name: &str) -> Self {
+        NameAlias {
+            name: name.to_owned(),
+            kind: AliasKind::Cname,
+            ttl: DEFAULT_ALIAS_TTL,
+            timeout: DEFAULT_REGISTRATION_TIMEOUT,
+        }
+    }
+
+    /// Publishes alias as the address record instead of CNAME.
+    pub fn address(mut self, address: IpAddr) -> Self {
+        self.kind = AliasKind::Address(address);
+        self
+    }
+
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
@@ -0,0 +1,33 @@
+/// Set of flags that control how records and services are published.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PublishFlags(u32);
+
+impl PublishFlags {
+    /// The record is unique and probing is required before it's announced.
+    pub const UNIQUE: PublishFlags = PublishFlags(1);
+    /// Don't probe the record, even if it's unique.
+    pub const NO_PROBE: PublishFlags = PublishFlags(2);
+    /// Don't announce the record.
+    pub const NO_ANNOUNCE: PublishFlags = PublishFlags(4);
+    /// Allow multiple local records of this type, even if it's unique.
+    pub const ALLOW_MULTIPLE: PublishFlags = PublishFlags(8);
+    /// Don't create a reverse (PTR) entry for the address.
+    pub const NO_REVERSE: PublishFlags = PublishFlags(16);
+    /// Don't add the local service cookie to the TXT record.
+    pub const NO_COOKIE: PublishFlags = PublishFlags(32);
+    /// Update the existing records instead of adding new ones.
+    pub const UPDATE: PublishFlags = PublishFlags(64);
+    /// Publish the record using wide area DNS.
+    pub const USE_WIDE_AREA: PublishFlags = PublishFlags(128);
+    /// Publish the record using multicast DNS.
+    pub const USE_MULTICAST: PublishFlags = PublishFlags(256);
+}
+
+/// Services and records are published using multicast DNS unless stated otherwise.
+impl Default for PublishFlags {
+    fn default() -> Self {
+        PublishFlags::USE_MULTICAST
+    }
+}
+
+impl_flags!(PublishFlags);
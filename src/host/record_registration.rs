@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use discovery::discovery_manager::ServiceProtocol;
+use discovery::dns_record::{RecordData, CLASS_IN};
+use host::publish_flags::PublishFlags;
+use host::service_registration::DEFAULT_REGISTRATION_TIMEOUT;
+
+/// TTL used by default, it's the same TTL Avahi uses for host name records.
+pub const DEFAULT_TTL: u32 = 120;
+
+/// Describes DNS record that should be published on the network. Use `new` to create
+/// registration with the mandatory fields and builder methods to set the rest.
+#[derive(Clone, Debug)]
+pub struct RecordRegistration {
+    pub name: String,
+    pub record_class: u16,
+    pub data: RecordData,
+    /// Time in seconds other hosts are allowed to cache the record for.
+    pub ttl: u32,
+    /// Index of the network interface to publish record on, `-1` means all interfaces.
+    pub interface: i32,
+    pub protocol: ServiceProtocol,
+    pub flags: PublishFlags,
+    /// Time to wait for record to be established, record that isn't established in
+    /// time is withdrawn.
+    pub timeout: Duration,
+}
+
+impl RecordRegistration {
+    pub fn new(name: &str, data: RecordData) -> Self {
+        RecordRegistration {
+            name: name.to_owned(),
+            record_class: CLASS_IN,
+            data,
+            ttl: DEFAULT_TTL,
+            interface: -1,
+            protocol: ServiceProtocol::Unspecified,
+            flags: PublishFlags::default(),
+            timeout: DEFAULT_REGISTRATION_TIMEOUT,
+        }
+    }
+
+    pub fn record_class(mut self, record_class: u16) -> Self {
+        self.record_class = record_class;
+        self
+    }
+
+    pub fn ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn interface(mut self, interface: i32) -> Self {
+        self.interface = interface;
+        self
+    }
+
+    pub fn protocol(mut self, protocol: ServiceProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn flags(mut self, flags: PublishFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use adapters::errors::Error;
+use discovery::discovery_manager::ServiceProtocol;
+use discovery::discovery_options::interface_index;
+use discovery::txt_record::TxtRecord;
+use host::publish_flags::PublishFlags;
+
+/// Time `HostManager::register_service` waits for service to be established by default.
+pub const DEFAULT_REGISTRATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Describes service that should be announced on the network. Use `new` to create
+/// registration with the mandatory fields and builder methods to set the rest.
+#[derive(Clone, Debug)]
+pub struct ServiceRegistration {
+    pub name: String,
+    pub service_type: String,
+    pub subtypes: Vec<String>,
+    /// Domain to publish service in, `None` means default domain (usually `local`).
+    pub domain: Option<String>,
+    /// Host that provides service, `None` means the local host.
+    pub host: Option<String>,
+    pub port: u16,
+    pub txt: TxtRecord,
+    /// Indexes of the network interfaces to publish service on, empty means all
+    /// interfaces.
+    pub interfaces: Vec<i32>,
+    pub protocol: ServiceProtocol,
+    pub flags: PublishFlags,
+    /// Time to wait for service to be established, service that isn't established in
+    /// time (e.g. because daemon doesn't respond) is withdrawn.
+    pub timeout: Duration,
+}
+
+impl ServiceRegistration {
+    pub fn new(name: &str, service_type: &str, port: u16) -> Self {
+        ServiceRegistration {
+            name: name.to_owned(),
+            service_type: service_type.to_owned(),
+            subtypes: Vec::new(),
+            domain: None,
+            host: None,
+            port,
+            txt: TxtRecord::new(),
+            interfaces: Vec::new(),
+            protocol: ServiceProtocol::Unspecified,
+            flags: PublishFlags::default(),
+            timeout: DEFAULT_REGISTRATION_TIMEOUT,
+        }
+    }
+
+    /// Adds service subtype, either in short (`_printer`) or in full
+    /// (`_printer._sub._http._tcp`) form.
+    pub fn subtype(mut self, subtype: &str) -> Self {
+        self.subtypes.push(subtype.to_owned());
+        self
+    }
+
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_owned());
+        self
+    }
+
+    pub fn host(mut self, host: &str) -> Self {
+        self.host = Some(host.to_owned());
+        self
+    }
+
+    pub fn txt(mut self, txt: TxtRecord) -> Self {
+        self.txt = txt;
+        self
+    }
+
+    /// Adds `key=value` entry to the TXT record, fails if key isn't valid.
+    pub fn txt_entry<V: AsRef<[u8]>>(mut self, key: &str, value: V) -> Result<Self, Error> {
+        self.txt.insert(key, value)?;
+        Ok(self)
+    }
+
+    /// Restricts announcement to the network interface with the specified index, can be
+    /// called multiple times to announce service on several interfaces.
+    pub fn interface(mut self, interface: i32) -> Self {
+        self.interfaces.push(interface);
+        self
+    }
+
+    /// Same as `interface`, but interface is specified by name (e.g. `br0`), fails if
+    /// there is no such interface.
+    pub fn interface_name(self, name: &str) -> Result<Self, Error> {
+        Ok(self.interface(interface_index(name)?))
+    }
+
+    /// Restricts announcement to either IPv4 or IPv6 network.
+    pub fn protocol(mut self, protocol: ServiceProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn flags(mut self, flags: PublishFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns subtypes in the full `<subtype>._sub.<service type>` form.
+    pub fn full_subtypes(&self) -> Vec<String> {
+        self.subtypes
+            .iter()
+            .map(|subtype| {
+                if subtype.contains("._sub.") {
+                    subtype.clone()
+                } else {
+                    format!("{}._sub.{}", subtype, self.service_type)
+                }
+            })
+            .collect()
+    }
+}
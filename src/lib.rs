@@ -3,6 +3,12 @@ extern crate log;
 
 extern crate libc;
 
+#[cfg(feature = "async")]
+extern crate futures;
+
+#[macro_use]
+mod flags;
+
 mod adapters;
 #[cfg(target_os = "linux")]
 mod bindings;
@@ -10,3 +16,5 @@ mod bindings;
 pub mod discovery;
 pub mod host;
 pub use adapters::errors;
+#[cfg(feature = "test-util")]
+pub use adapters::fake::FakeAdapter;